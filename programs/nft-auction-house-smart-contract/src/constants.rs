@@ -1,10 +1,136 @@
 use anchor_lang::prelude::*;
 
-// pub const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
-pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
-pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
-pub const TENSOR_CNFT_PROGRAM_ID: Pubkey = pubkey!("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp");
+#[cfg(
+    any(
+        all(feature = "devnet", feature = "mainnet"),
+        all(feature = "devnet", feature = "localnet"),
+        all(feature = "mainnet", feature = "localnet"),
+        not(any(feature = "devnet", feature = "mainnet", feature = "localnet"))
+    )
+)]
+compile_error!("enable exactly one of the `devnet`, `mainnet` or `localnet` features");
 
-pub const PACK_PRICES: [u8; 6] = [1, 25, 50, 100, 250, 250];
+// Mock randomness lets the admin pick pack contents, so it must never reach mainnet
+#[cfg(all(feature = "mock-vrf", feature = "mainnet"))]
+compile_error!("the `mock-vrf` feature cannot be enabled together with `mainnet`");
+
+#[cfg(feature = "mainnet")]
+mod cluster {
+    use super::*;
+
+    pub const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+    pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+    pub const TENSOR_CNFT_PROGRAM_ID: Pubkey = pubkey!("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp");
+    pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+}
+
+#[cfg(feature = "devnet")]
+mod cluster {
+    use super::*;
+
+    pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+    pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+    pub const TENSOR_CNFT_PROGRAM_ID: Pubkey = pubkey!("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp");
+    pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+}
+
+// Programs are cloned from devnet into the local validator; the payment mint is whatever
+// test mint `initialize` is given
+#[cfg(feature = "localnet")]
+mod cluster {
+    use super::*;
+
+    pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+    pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+    pub const TENSOR_CNFT_PROGRAM_ID: Pubkey = pubkey!("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp");
+    pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+}
+
+pub use cluster::*;
+
+// Whether `initialize` may store a payment mint other than `USDC_MINT`
+pub const ALLOW_CUSTOM_PAYMENT_MINT: bool = cfg!(feature = "localnet");
+
+// Same on every cluster
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!(
+    "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+);
+pub const SPL_NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = mpl_token_metadata::ID;
+
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+// Pyth SOL/USD feed id
+pub const SOL_USD_FEED_ID: [u8; 32] = [
+    239, 13, 139, 111, 218, 44, 235, 164, 29, 161, 93, 64, 149, 209, 218, 57, 42, 13, 47, 142, 208,
+    198, 199, 188, 15, 76, 250, 200, 194, 128, 181, 109,
+];
+pub const MAX_PRICE_AGE_SECS: i64 = 60;
+// Confidence interval allowed relative to the price, in basis points
+pub const MAX_PRICE_CONFIDENCE_BPS: u64 = 200;
 
 pub const CONFIG_SEED: &[u8] = b"auction_house";
+pub const COLLECTION_AUTHORITY_SEED: &[u8] = b"collection_authority";
+pub const COLLECTION_CONFIG_SEED: &[u8] = b"collection_config";
+pub const CARD_SET_SEED: &[u8] = b"card_set";
+pub const CARD_DEFINITION_SEED: &[u8] = b"card_definition";
+pub const PACK_CONFIG_SEED: &[u8] = b"pack_config";
+pub const PACK_PURCHASE_SEED: &[u8] = b"pack_purchase";
+pub const CRAFT_REQUEST_SEED: &[u8] = b"craft_request";
+pub const EVOLUTION_STAKE_SEED: &[u8] = b"evolution_stake";
+pub const EVOLUTION_RECORD_SEED: &[u8] = b"evolution_record";
+pub const PLAYER_PROFILE_SEED: &[u8] = b"player_profile";
+pub const PLAYER_PACK_STATE_SEED: &[u8] = b"player_pack_state";
+pub const DROP_TABLE_SEED: &[u8] = b"drop_table";
+pub const PACK_POOL_SEED: &[u8] = b"pack_pool";
+pub const LEAF_METADATA_SEED: &[u8] = b"leaf_metadata";
+pub const NFT_LIST_SEED: &[u8] = b"nft_list";
+pub const FEE_SCHEDULE_SEED: &[u8] = b"fee_schedule";
+pub const REFERRAL_POOL_SEED: &[u8] = b"referral_pool";
+pub const REFERRER_SEED: &[u8] = b"referrer";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const BUYBACK_RESERVE_SEED: &[u8] = b"buyback_reserve";
+pub const ROLES_SEED: &[u8] = b"roles";
+// Bubblegum PDA that signs collection verification CPIs into Token Metadata
+pub const BUBBLEGUM_SIGNER_SEED: &[u8] = b"collection_cpi";
+pub const WITHDRAWAL_REQUEST_SEED: &[u8] = b"withdrawal_request";
+pub const PACK_RANDOMNESS_BINDING_SEED: &[u8] = b"pack_randomness_binding";
+pub const CRAFT_RANDOMNESS_BINDING_SEED: &[u8] = b"craft_randomness_binding";
+pub const MOCK_RANDOMNESS_SEED: &[u8] = b"mock_randomness";
+
+// Common, Uncommon, Rare, Holo Rare, Secret Rare
+pub const RARITY_TIERS: usize = 5;
+pub const MAX_SET_TREES: usize = 8;
+pub const MAX_NFT_LIST_ENTRIES: usize = 25;
+pub const MAX_CARDS_PER_PURCHASE: usize = 60;
+pub const MAX_POOL_ENTRIES: usize = 256;
+pub const MAX_PAYMENT_MINTS: usize = 4;
+pub const MAX_ROLE_MEMBERS: usize = 16;
+// SOL, USDC and every extra payment mint
+pub const MAX_REFERRAL_BALANCES: usize = MAX_PAYMENT_MINTS + 2;
+pub const MAX_TREASURY_LIMITS: usize = MAX_PAYMENT_MINTS + 2;
+
+// Window the treasury withdrawal cap applies to
+pub const WITHDRAWAL_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+// Shortest delay a queued withdrawal can have, so a cap-sized drain can't be chained instantly
+pub const MIN_WITHDRAWAL_TIMELOCK_SECS: i64 = WITHDRAWAL_WINDOW_SECS;
+pub const PURCHASE_DAY_SECS: i64 = 24 * 60 * 60;
+
+// Stands in for the mint of pack prices paid in lamports
+pub const SOL_PAYMENT_MINT: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+// Slots between a slot-hash commit and the slot whose hash is revealed
+pub const SLOT_HASH_REVEAL_DELAY: u64 = 2;
+// Slots before an unrevealed pack can get new randomness; past the ~512-slot SlotHashes window
+pub const RANDOMNESS_RETRY_SLOTS: u64 = 600;
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+// Metaplex caps creators at five; one slot is taken by the collection authority PDA, so a
+// collection can list at most four royalty creators
+pub const MAX_CREATORS: usize = 5;
+pub const MAX_ROYALTY_CREATORS: usize = MAX_CREATORS - 1;
+pub const MAX_SELLER_FEE_BASIS_POINTS: u16 = 10000;
@@ -0,0 +1,312 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::RARITY_TIERS;
+use crate::errors::NftAuctionError;
+use crate::state::{ DrawnCard, DropTable, PackPool };
+
+pub struct DrawOutcome {
+    pub cards: Vec<DrawnCard>,
+    pub pity_counter: u16,
+}
+
+// Each card slot gets its own 32 bytes derived from the VRF output and the slot number
+fn slot_entropy(randomness: &[u8; 64], slot: u32) -> (u64, u64) {
+    let digest = hashv(&[randomness, &slot.to_le_bytes()]).to_bytes();
+    (
+        u64::from_le_bytes(digest[0..8].try_into().unwrap()),
+        u64::from_le_bytes(digest[8..16].try_into().unwrap()),
+    )
+}
+
+// Weighted pick over tiers at or above `min_tier`; falls back to the lowest stocked tier
+// when those tiers carry no weight
+fn pick_tier(drop_table: &DropTable, tier_roll: u64, min_tier: usize) -> Result<usize> {
+    let total_weight: u64 = drop_table.rarity_weights[min_tier..]
+        .iter()
+        .map(|w| *w as u64)
+        .sum();
+
+    if total_weight == 0 {
+        return (min_tier..RARITY_TIERS)
+            .find(|tier| drop_table.tier_sizes[*tier] > 0)
+            .ok_or(error!(NftAuctionError::EmptyRarityTier));
+    }
+
+    let mut roll = tier_roll % total_weight;
+    for tier in min_tier..RARITY_TIERS {
+        let weight = drop_table.rarity_weights[tier] as u64;
+        if roll < weight {
+            return Ok(tier);
+        }
+        roll -= weight;
+    }
+
+    Ok(RARITY_TIERS - 1)
+}
+
+// Picks a card proportionally to the remaining copies and removes it from the pool.
+// Restricted to tiers at or above `min_tier` while any of those remain.
+fn take_from_pool(pool: &mut PackPool, roll: u64, min_tier: usize) -> Result<DrawnCard> {
+    let eligible: u64 = pool.entries
+        .iter()
+        .filter(|entry| (entry.rarity as usize) >= min_tier)
+        .map(|entry| entry.remaining as u64)
+        .sum();
+    let (min_tier, available) = if eligible > 0 {
+        (min_tier, eligible)
+    } else {
+        (0, pool.total_remaining)
+    };
+    require!(available > 0, NftAuctionError::PackPoolExhausted);
+
+    let mut roll = roll % available;
+    for entry in pool.entries.iter_mut() {
+        if (entry.rarity as usize) < min_tier {
+            continue;
+        }
+        let remaining = entry.remaining as u64;
+        if roll < remaining {
+            entry.remaining -= 1;
+            pool.total_remaining -= 1;
+            return Ok(DrawnCard {
+                rarity: entry.rarity,
+                index: entry.index,
+            });
+        }
+        roll -= remaining;
+    }
+
+    Err(NftAuctionError::PackPoolExhausted.into())
+}
+
+pub fn draw_from_tier(randomness: &[u8; 64], tier_size: u16, rarity: u8) -> Result<DrawnCard> {
+    require!(tier_size > 0, NftAuctionError::EmptyRarityTier);
+
+    let (_, card_roll) = slot_entropy(randomness, 0);
+    Ok(DrawnCard {
+        rarity,
+        index: (card_roll % (tier_size as u64)) as u16,
+    })
+}
+
+/// Recomputes the cards a pack purchase yields from its randomness, the drop table it was
+/// bought under and the buyer's pity counter before the reveal. Reveals call this on chain,
+/// so anyone can replay a purchase off chain and check the result.
+///
+/// `pity_counter` counts consecutive packs without a card of the table's pity tier; once it
+/// would reach the threshold, the last card of the pack is forced up to that tier.
+///
+/// With a `pool`, cards are drawn from its remaining copies instead of the table's weights
+/// and the pool is decremented; pass a copy of the pool state to simulate off chain.
+pub fn simulate_draw(
+    randomness: &[u8; 64],
+    drop_table: &DropTable,
+    mut pool: Option<&mut PackPool>,
+    cards_per_pack: u8,
+    pack_count: u8,
+    pity_counter: u16
+) -> Result<DrawOutcome> {
+    let cards_per_pack = cards_per_pack as u32;
+    let pity_enabled = drop_table.pity_threshold > 0;
+    let pity_tier = drop_table.pity_rarity as usize;
+
+    let mut pity_counter = pity_counter;
+    let mut cards = Vec::with_capacity((pack_count as usize) * (cards_per_pack as usize));
+
+    for pack in 0..pack_count as u32 {
+        let force_pity =
+            pity_enabled && pity_counter.saturating_add(1) >= drop_table.pity_threshold;
+        let mut pity_hit = false;
+
+        for card in 0..cards_per_pack {
+            let (tier_roll, card_roll) = slot_entropy(randomness, pack * cards_per_pack + card);
+
+            let min_tier = if force_pity && !pity_hit && card == cards_per_pack - 1 {
+                pity_tier
+            } else {
+                0
+            };
+            let drawn = match pool.as_deref_mut() {
+                Some(pool) => take_from_pool(pool, tier_roll, min_tier)?,
+                None => {
+                    let rarity = pick_tier(drop_table, tier_roll, min_tier)?;
+                    let tier_size = drop_table.tier_sizes[rarity];
+                    require!(tier_size > 0, NftAuctionError::EmptyRarityTier);
+
+                    DrawnCard {
+                        rarity: rarity as u8,
+                        index: (card_roll % (tier_size as u64)) as u16,
+                    }
+                }
+            };
+            pity_hit |= (drawn.rarity as usize) >= pity_tier;
+
+            cards.push(drawn);
+        }
+
+        if pity_enabled {
+            pity_counter = if pity_hit { 0 } else { pity_counter.saturating_add(1) };
+        }
+    }
+
+    Ok(DrawOutcome { cards, pity_counter })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PoolEntry;
+
+    const SEED: [u8; 64] = [7u8; 64];
+
+    fn drop_table(
+        rarity_weights: [u16; RARITY_TIERS],
+        tier_sizes: [u16; RARITY_TIERS],
+        pity_threshold: u16,
+        pity_rarity: u8
+    ) -> DropTable {
+        DropTable {
+            layout_version: DropTable::LAYOUT_VERSION,
+            pack_config: Pubkey::default(),
+            version: 1,
+            rarity_weights,
+            tier_sizes,
+            pity_threshold,
+            pity_rarity,
+            activated: true,
+            activated_at: 0,
+            hash: [0u8; 32],
+            bump: 0,
+        }
+    }
+
+    fn error_code<T>(result: Result<T>) -> u32 {
+        match result {
+            Err(Error::AnchorError(error)) => error.error_code_number,
+            _ => panic!("expected an anchor error"),
+        }
+    }
+
+    #[test]
+    fn pick_tier_respects_weight_boundaries() {
+        let table = drop_table([3, 2, 0, 0, 1], [1; RARITY_TIERS], 0, 0);
+
+        assert_eq!(pick_tier(&table, 0, 0).unwrap(), 0);
+        assert_eq!(pick_tier(&table, 2, 0).unwrap(), 0);
+        assert_eq!(pick_tier(&table, 3, 0).unwrap(), 1);
+        assert_eq!(pick_tier(&table, 4, 0).unwrap(), 1);
+        assert_eq!(pick_tier(&table, 5, 0).unwrap(), 4);
+        // Rolls wrap around the total weight
+        assert_eq!(pick_tier(&table, 6, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn pick_tier_only_rolls_tiers_at_or_above_min_tier() {
+        let table = drop_table([100, 0, 0, 1, 0], [1; RARITY_TIERS], 0, 0);
+
+        for roll in 0..10 {
+            assert_eq!(pick_tier(&table, roll, 2).unwrap(), 3);
+        }
+    }
+
+    #[test]
+    fn pick_tier_falls_back_to_lowest_stocked_tier_without_weight() {
+        let table = drop_table([1, 0, 0, 0, 0], [4, 4, 0, 4, 0], 0, 0);
+
+        assert_eq!(pick_tier(&table, 0, 2).unwrap(), 3);
+    }
+
+    #[test]
+    fn pick_tier_fails_when_no_tier_is_stocked() {
+        let table = drop_table([1, 0, 0, 0, 0], [4, 0, 0, 0, 0], 0, 0);
+
+        assert_eq!(
+            error_code(pick_tier(&table, 0, 1)),
+            u32::from(NftAuctionError::EmptyRarityTier)
+        );
+    }
+
+    #[test]
+    fn simulate_draw_rejects_weighted_empty_tier() {
+        let table = drop_table([0, 0, 1, 0, 0], [5, 5, 0, 5, 5], 0, 0);
+
+        assert_eq!(
+            error_code(simulate_draw(&SEED, &table, None, 1, 1, 0)),
+            u32::from(NftAuctionError::EmptyRarityTier)
+        );
+    }
+
+    #[test]
+    fn simulate_draw_counts_packs_without_a_pity_card() {
+        let table = drop_table([1, 0, 0, 0, 0], [10, 0, 0, 0, 2], 3, 4);
+
+        let outcome = simulate_draw(&SEED, &table, None, 2, 2, 0).unwrap();
+        assert!(outcome.cards.iter().all(|card| card.rarity == 0));
+        assert_eq!(outcome.pity_counter, 2);
+    }
+
+    #[test]
+    fn simulate_draw_forces_last_card_at_threshold_and_resets() {
+        let table = drop_table([1, 0, 0, 0, 0], [10, 0, 0, 0, 2], 3, 4);
+
+        let outcome = simulate_draw(&SEED, &table, None, 2, 1, 2).unwrap();
+        assert_eq!(outcome.cards[0].rarity, 0);
+        assert_eq!(outcome.cards[1].rarity, 4);
+        assert!(outcome.cards[1].index < 2);
+        assert_eq!(outcome.pity_counter, 0);
+
+        // Third pack of a fresh counter is the one forced up
+        let outcome = simulate_draw(&SEED, &table, None, 1, 4, 0).unwrap();
+        let rarities: Vec<u8> = outcome.cards.iter().map(|card| card.rarity).collect();
+        assert_eq!(rarities, vec![0, 0, 4, 0]);
+        assert_eq!(outcome.pity_counter, 1);
+    }
+
+    #[test]
+    fn simulate_draw_leaves_counter_alone_when_pity_is_disabled() {
+        let table = drop_table([1, 0, 0, 0, 0], [10, 0, 0, 0, 2], 0, 4);
+
+        let outcome = simulate_draw(&SEED, &table, None, 1, 5, 7).unwrap();
+        assert!(outcome.cards.iter().all(|card| card.rarity == 0));
+        assert_eq!(outcome.pity_counter, 7);
+    }
+
+    #[test]
+    fn simulate_draw_takes_pity_card_from_pool() {
+        let table = drop_table([1, 0, 0, 0, 0], [10, 0, 0, 0, 2], 1, 4);
+        let mut pool = PackPool {
+            version: PackPool::VERSION,
+            pack_config: Pubkey::default(),
+            entries: vec![
+                PoolEntry { rarity: 0, index: 0, remaining: 50 },
+                PoolEntry { rarity: 4, index: 1, remaining: 1 }
+            ],
+            total_remaining: 51,
+            reserved: 0,
+            bump: 0,
+        };
+
+        let outcome = simulate_draw(&SEED, &table, Some(&mut pool), 1, 1, 0).unwrap();
+        assert_eq!(outcome.cards[0].rarity, 4);
+        assert_eq!(pool.entries[1].remaining, 0);
+        assert_eq!(pool.total_remaining, 50);
+    }
+
+    #[test]
+    fn simulate_draw_fails_on_exhausted_pool() {
+        let table = drop_table([1, 0, 0, 0, 0], [10, 0, 0, 0, 0], 0, 0);
+        let mut pool = PackPool {
+            version: PackPool::VERSION,
+            pack_config: Pubkey::default(),
+            entries: vec![PoolEntry { rarity: 0, index: 0, remaining: 1 }],
+            total_remaining: 1,
+            reserved: 0,
+            bump: 0,
+        };
+
+        assert_eq!(
+            error_code(simulate_draw(&SEED, &table, Some(&mut pool), 1, 2, 0)),
+            u32::from(NftAuctionError::PackPoolExhausted)
+        );
+    }
+}
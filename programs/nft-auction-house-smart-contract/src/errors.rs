@@ -31,6 +31,231 @@ pub enum NftAuctionError {
 
     #[msg("NFT list is full")]
     NftListFull,
+
+    #[msg("Royalty basis points exceed 10000")]
+    InvalidRoyaltyBasisPoints,
+
+    #[msg("Too many creators for a collection")]
+    TooManyCreators,
+
+    #[msg("Creator shares must sum to 100")]
+    InvalidCreatorShares,
+
+    #[msg("Signer is not the configured admin")]
+    Unauthorized,
+
+    #[msg("Merkle tree is not registered to this card set")]
+    UnknownMerkleTree,
+
+    #[msg("Card set has no room for another merkle tree")]
+    TooManyMerkleTrees,
+
+    #[msg("Invalid rarity tier")]
+    InvalidRarity,
+
+    #[msg("Card index does not match the next free slot in its tier")]
+    InvalidCardIndex,
+
+    #[msg("Metadata field exceeds its maximum length")]
+    MetadataTooLong,
+
+    #[msg("Drawn rarity tier has no cards")]
+    EmptyRarityTier,
+
+    #[msg("Drop weights must not be all zero")]
+    InvalidRarityWeights,
+
+    #[msg("Pack purchase has already been revealed")]
+    AlreadyRevealed,
+
+    #[msg("Platform vault does not match the configured vault")]
+    InvalidPlatformVault,
+
+    #[msg("Crafting is disabled for this card set")]
+    CraftingDisabled,
+
+    #[msg("Craft inputs must be the configured number of cards from one tier below the top")]
+    InvalidCraftInputs,
+
+    #[msg("Card definition does not evolve into the given target")]
+    InvalidEvolutionTarget,
+
+    #[msg("Evolution cost was not provided")]
+    EvolutionCostMissing,
+
+    #[msg("Evolution staking period has not elapsed")]
+    EvolutionNotReady,
+
+    #[msg("Player profile tracks too many pack types")]
+    TooManyTrackedPacks,
+
+    #[msg("Pack config has no active drop table")]
+    NoActiveDropTable,
+
+    #[msg("Drop table is already activated and can no longer change")]
+    DropTableActivated,
+
+    #[msg("Drop table version must be newer than the active one")]
+    StaleDropTableVersion,
+
+    #[msg("Pack pool cannot fill this purchase")]
+    PackPoolExhausted,
+
+    #[msg("Pack pool account is missing or does not match the pack config")]
+    InvalidPackPool,
+
+    #[msg("Pack pool has no room for more card entries")]
+    PackPoolFull,
+
+    #[msg("Randomness account does not belong to the selected provider")]
+    InvalidRandomnessAccount,
+
+    #[msg("Accounts required by the randomness provider are missing")]
+    MissingRandomnessAccounts,
+
+    #[msg("Randomness can no longer be revealed")]
+    RandomnessExpired,
+
+    #[msg("Randomness account is already bound to another request")]
+    RandomnessAlreadyBound,
+
+    #[msg("Randomness has already been consumed")]
+    RandomnessAlreadyConsumed,
+
+    #[msg("Randomness account is bound to a different request")]
+    RandomnessBindingMismatch,
+
+    #[msg("Pack config does not accept this payment mint")]
+    PaymentMintNotAccepted,
+
+    #[msg("Pack config has no room for another payment mint")]
+    TooManyPaymentMints,
+
+    #[msg("Payment token account does not match the mint or owner")]
+    InvalidPaymentAccount,
+
+    #[msg("Price account is missing or not a verified SOL/USD price update")]
+    InvalidPriceAccount,
+
+    #[msg("Oracle price is too old")]
+    StalePrice,
+
+    #[msg("Oracle price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+
+    #[msg("Pack price exceeds the maximum payment")]
+    SlippageExceeded,
+
+    #[msg("Fee shares must sum to 10000 basis points")]
+    InvalidFeeSplit,
+
+    #[msg("Buyers cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("Buyer is already attributed to a different referrer")]
+    ReferrerLocked,
+
+    #[msg("Referrer tracks too many payment mints")]
+    TooManyReferralBalances,
+
+    #[msg("No referral balance to claim")]
+    NothingToClaim,
+
+    #[msg("Withdrawal exceeds the treasury's 24h cap; queue it instead")]
+    TreasuryCapExceeded,
+
+    #[msg("Treasury tracks too many withdrawal limits")]
+    TooManyTreasuryLimits,
+
+    #[msg("Withdrawal request is still timelocked")]
+    TimelockNotElapsed,
+
+    #[msg("Withdrawal accounts do not match the request")]
+    InvalidWithdrawal,
+
+    #[msg("Buybacks are paused until the reserve recovers")]
+    BuybacksPaused,
+
+    #[msg("Buyback reserve ratios are invalid")]
+    InvalidReserveRatio,
+
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+
+    #[msg("Too many role members")]
+    TooManyRoleMembers,
+
+    #[msg("The last super admin cannot be revoked")]
+    LastSuperAdmin,
+
+    #[msg("Signer is not the configured minter authority")]
+    NotMinter,
+
+    #[msg("Config account has an unrecognized layout")]
+    InvalidConfigLayout,
+
+    #[msg("Config is already at the current version")]
+    ConfigUpToDate,
+
+    #[msg("Too many packs in one purchase")]
+    PacksPerPurchaseExceeded,
+
+    #[msg("Wallet has reached the pack limit for this drop")]
+    WalletPackLimitReached,
+
+    #[msg("Wallet has reached today's pack limit")]
+    DailyPackLimitReached,
+
+    #[msg("Purchases are too close together")]
+    PurchaseCooldown,
+
+    #[msg("Creator is listed more than once")]
+    DuplicateCreator,
+
+    #[msg("Creator address is reserved for the collection authority")]
+    ReservedCreatorAddress,
+
+    #[msg("Leaf metadata matches neither its record nor its card definition")]
+    UnknownMetadataRevision,
+
+    #[msg("Card definition has too many metadata revisions")]
+    TooManyMetadataRevisions,
+
+    #[msg("No card is waiting to be claimed")]
+    NoCardToClaim,
+
+    #[msg("Card definition does not match the card being claimed")]
+    CardDefinitionMismatch,
+
+    #[msg("Leaf is not delegated to its evolution stake")]
+    LeafNotStaked,
+
+    #[msg("Randomness request can still be revealed")]
+    RandomnessNotStale,
+
+    #[msg("Fee percentage must be at most 10000 basis points")]
+    InvalidFeePercentage,
+
+    #[msg("Account has an unrecognized layout")]
+    InvalidAccountLayout,
+
+    #[msg("Account is already at the current version")]
+    AccountUpToDate,
+
+    #[msg("Leaf metadata record does not belong to this leaf")]
+    InvalidLeafMetadata,
+
+    #[msg("Merkle tree's delegate must be the collection authority")]
+    InvalidTreeDelegate,
+
+    #[msg("Earlier purchases must be revealed first")]
+    RevealOutOfOrder,
+
+    #[msg("Withdrawal timelock is below the minimum")]
+    TimelockTooShort,
+
+    #[msg("Account version has no migration path to the current layout")]
+    UnknownAccountVersion,
 }
 
 #[error_code]
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct CardMetadataUpdated {
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub old_data_hash: [u8; 32],
+    pub new_data_hash: [u8; 32],
+}
+
+#[event]
+pub struct CardCrafted {
+    pub user: Pubkey,
+    pub card_set: Pubkey,
+    pub rarity: u8,
+    pub index: u16,
+}
+
+#[event]
+pub struct CardEvolved {
+    pub owner: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub source_definition: Pubkey,
+    pub target_definition: Pubkey,
+}
+
+#[event]
+pub struct DropTableActivated {
+    pub pack_config: Pubkey,
+    pub drop_table: Pubkey,
+    pub version: u32,
+    pub hash: [u8; 32],
+}
+
+#[event]
+pub struct CardBurned {
+    pub owner: Pubkey,
+    pub card_definition: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub reward: u64,
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{ self, get_associated_token_address_with_program_id };
+use anchor_spl::token_interface;
+use crate::errors::NftAuctionError;
+use crate::state::FeeSplit;
+
+// Wallets (or, for token payments, their ATAs of the payment mint) receiving each share
+pub struct FeeRecipients<'info> {
+    pub treasury: AccountInfo<'info>,
+    pub creator: AccountInfo<'info>,
+    pub partner: AccountInfo<'info>,
+    pub referral_pool: AccountInfo<'info>,
+}
+
+impl<'info> FeeRecipients<'info> {
+    fn with_amounts(&self, split: &FeeSplit) -> [(&AccountInfo<'info>, u64); 4] {
+        [
+            (&self.treasury, split.treasury),
+            (&self.creator, split.creator),
+            (&self.partner, split.partner),
+            (&self.referral_pool, split.referral),
+        ]
+    }
+}
+
+// Creator and partner shares too small to leave their wallet rent exempt would fail the
+// transfer, so they go to the treasury instead
+pub fn distribute_sol<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    recipients: &FeeRecipients<'info>,
+    split: &FeeSplit
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let below_rent = |recipient: &AccountInfo, amount: u64| {
+        amount > 0 &&
+            !rent.is_exempt(recipient.lamports().saturating_add(amount), recipient.data_len())
+    };
+
+    let mut split = split.clone();
+    if below_rent(&recipients.creator, split.creator) {
+        split.treasury += split.creator;
+        split.creator = 0;
+    }
+    if below_rent(&recipients.partner, split.partner) {
+        split.treasury += split.partner;
+        split.partner = 0;
+    }
+
+    for (recipient, amount) in recipients.with_amounts(&split) {
+        if amount == 0 {
+            continue;
+        }
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), system_program::Transfer {
+                from: payer.clone(),
+                to: recipient.clone(),
+            }),
+            amount
+        )?;
+    }
+
+    Ok(())
+}
+
+// Tops `account` up to rent exemption so later payments of any size can land in it
+pub fn fund_rent_exempt<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(account.data_len())
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            }),
+            rent_due
+        )?;
+    }
+
+    Ok(())
+}
+
+pub struct TokenPayment<'info> {
+    pub from: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub decimals: u8,
+    pub token_program: AccountInfo<'info>,
+}
+
+pub fn distribute_tokens<'info>(
+    payment: &TokenPayment<'info>,
+    recipients: &FeeRecipients<'info>,
+    split: &FeeSplit
+) -> Result<()> {
+    for (recipient, amount) in recipients.with_amounts(split) {
+        if amount == 0 {
+            continue;
+        }
+        token_interface::transfer_checked(
+            CpiContext::new(payment.token_program.clone(), token_interface::TransferChecked {
+                from: payment.from.clone(),
+                mint: payment.mint.clone(),
+                to: recipient.clone(),
+                authority: payment.authority.clone(),
+            }),
+            amount,
+            payment.decimals
+        )?;
+    }
+
+    Ok(())
+}
+
+// Accounts needed to create recipient ATAs of the payment mint on the fly
+pub struct RecipientAtas<'info> {
+    pub mint: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+}
+
+impl<'info> RecipientAtas<'info> {
+    // Checks `ata` is the wallet's ATA of the mint and creates it if missing
+    pub fn resolve(
+        &self,
+        ata: Option<AccountInfo<'info>>,
+        wallet: AccountInfo<'info>
+    ) -> Result<AccountInfo<'info>> {
+        let ata = ata.ok_or(NftAuctionError::MissingVaultAta)?;
+        require_keys_eq!(
+            ata.key(),
+            get_associated_token_address_with_program_id(
+                &wallet.key(),
+                &self.mint.key(),
+                &self.token_program.key()
+            ),
+            NftAuctionError::MissingVaultAta
+        );
+
+        associated_token::create_idempotent(
+            CpiContext::new(self.associated_token_program.clone(), associated_token::Create {
+                payer: self.payer.clone(),
+                associated_token: ata.clone(),
+                authority: wallet,
+                mint: self.mint.clone(),
+                system_program: self.system_program.clone(),
+                token_program: self.token_program.clone(),
+            })
+        )?;
+
+        Ok(ata)
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    events::DropTableActivated,
+    state::{ CardSet, DropTable, PackConfig, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct ActivateDropTable<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(mut, has_one = card_set)]
+    pub pack_config: Account<'info, PackConfig>,
+
+    #[account(
+        mut,
+        has_one = pack_config,
+        constraint = !drop_table.activated @ NftAuctionError::DropTableActivated
+    )]
+    pub drop_table: Account<'info, DropTable>,
+}
+
+pub fn activate_drop_table(ctx: Context<ActivateDropTable>) -> Result<()> {
+    let card_set = &ctx.accounts.card_set;
+    let pack_config = &mut ctx.accounts.pack_config;
+    let drop_table = &mut ctx.accounts.drop_table;
+
+    require!(
+        drop_table.version > pack_config.drop_table_version,
+        NftAuctionError::StaleDropTableVersion
+    );
+
+    for (tier, weight) in drop_table.rarity_weights.iter().enumerate() {
+        if *weight > 0 {
+            require!(card_set.cards_by_rarity[tier] > 0, NftAuctionError::EmptyRarityTier);
+        }
+    }
+    if drop_table.pity_threshold > 0 {
+        require!(
+            card_set.cards_by_rarity[drop_table.pity_rarity as usize..]
+                .iter()
+                .any(|count| *count > 0),
+            NftAuctionError::EmptyRarityTier
+        );
+    }
+
+    drop_table.tier_sizes = card_set.cards_by_rarity;
+    drop_table.activated = true;
+    drop_table.activated_at = Clock::get()?.unix_timestamp;
+    drop_table.hash = drop_table.compute_hash();
+
+    pack_config.drop_table = drop_table.key();
+    pack_config.drop_table_version = drop_table.version;
+
+    emit!(DropTableActivated {
+        pack_config: pack_config.key(),
+        drop_table: drop_table.key(),
+        version: drop_table.version,
+        hash: drop_table.hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_DEFINITION_SEED, CARD_SET_SEED, RARITY_TIERS, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardDefinition, CardSet, EvolutionCost, Role, Roles },
+    utils::validate_metadata,
+};
+
+#[derive(Accounts)]
+#[instruction(rarity: u8, index: u16)]
+pub struct AddCardDefinition<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CardDefinition::INIT_SPACE,
+        seeds = [CARD_DEFINITION_SEED, card_set.key().as_ref(), &[rarity], &index.to_le_bytes()],
+        bump
+    )]
+    pub card_definition: Account<'info, CardDefinition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_card_definition(
+    ctx: Context<AddCardDefinition>,
+    rarity: u8,
+    index: u16,
+    pack_id: u8,
+    name: String,
+    symbol: String,
+    uri: String
+) -> Result<()> {
+    require!((rarity as usize) < RARITY_TIERS, NftAuctionError::InvalidRarity);
+    validate_metadata(&name, &symbol, &uri)?;
+
+    // Cards are numbered densely per tier so reveals can pick an index by modulo
+    let card_set = &mut ctx.accounts.card_set;
+    require!(
+        card_set.cards_by_rarity[rarity as usize] == index,
+        NftAuctionError::InvalidCardIndex
+    );
+    card_set.cards_by_rarity[rarity as usize] = index
+        .checked_add(1)
+        .ok_or(NftAuctionError::InvalidCardIndex)?;
+
+    let card_definition = &mut ctx.accounts.card_definition;
+    card_definition.card_set = card_set.key();
+    card_definition.rarity = rarity;
+    card_definition.index = index;
+    card_definition.pack_id = pack_id;
+    card_definition.name = name;
+    card_definition.symbol = symbol;
+    card_definition.uri = uri;
+    card_definition.minted = 0;
+    card_definition.burned = 0;
+    card_definition.evolves_into = None;
+    card_definition.evolution_cost = EvolutionCost::Free;
+    card_definition.version = CardDefinition::VERSION;
+    card_definition.bump = ctx.bumps.card_definition;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        MAX_SET_TREES,
+        ROLES_SEED,
+    },
+    errors::NftAuctionError,
+    state::{ CardSet, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct AddMerkleTree<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    /// CHECK: Bubblegum tree minted into by this set
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`, read by hand in the handler
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID,
+        owner = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA that crafted and evolved cards are minted with as tree delegate
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+}
+
+// Bubblegum `TreeConfig`: discriminator, then `tree_creator` and `tree_delegate`
+const TREE_DELEGATE_OFFSET: usize = 8 + 32;
+
+pub fn add_merkle_tree(ctx: Context<AddMerkleTree>) -> Result<()> {
+    let card_set = &mut ctx.accounts.card_set;
+    let merkle_tree = ctx.accounts.merkle_tree.key();
+
+    if card_set.merkle_trees.contains(&merkle_tree) {
+        return Ok(());
+    }
+    require!(card_set.merkle_trees.len() < MAX_SET_TREES, NftAuctionError::TooManyMerkleTrees);
+
+    // Claims mint without the admin, so a tree with any other delegate would fail only after the
+    // player's inputs were burned
+    let tree_config = ctx.accounts.tree_authority.try_borrow_data()?;
+    require!(
+        tree_config.len() >= TREE_DELEGATE_OFFSET + 32 &&
+            tree_config[..8] == hash(b"account:TreeConfig").to_bytes()[..8],
+        NftAuctionError::InvalidTreeDelegate
+    );
+    let tree_delegate = Pubkey::try_from(
+        &tree_config[TREE_DELEGATE_OFFSET..TREE_DELEGATE_OFFSET + 32]
+    ).map_err(|_| error!(NftAuctionError::InvalidTreeDelegate))?;
+    require_keys_eq!(
+        tree_delegate,
+        ctx.accounts.collection_authority.key(),
+        NftAuctionError::InvalidTreeDelegate
+    );
+
+    card_set.merkle_trees.push(merkle_tree);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, MAX_POOL_ENTRIES, PACK_POOL_SEED, RARITY_TIERS, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, PackConfig, PackPool, PoolEntry, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct AddPoolCards<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(has_one = card_set)]
+    pub pack_config: Account<'info, PackConfig>,
+
+    #[account(
+        mut,
+        seeds = [PACK_POOL_SEED, pack_config.key().as_ref()],
+        bump = pack_pool.bump,
+        has_one = pack_config
+    )]
+    pub pack_pool: Account<'info, PackPool>,
+}
+
+// `remaining` on each entry is the number of copies added to the pool
+pub fn add_pool_cards(ctx: Context<AddPoolCards>, cards: Vec<PoolEntry>) -> Result<()> {
+    let card_set = &ctx.accounts.card_set;
+    let pack_pool = &mut ctx.accounts.pack_pool;
+
+    for card in cards.iter() {
+        require!((card.rarity as usize) < RARITY_TIERS, NftAuctionError::InvalidRarity);
+        require!(
+            card.index < card_set.cards_by_rarity[card.rarity as usize],
+            NftAuctionError::InvalidCardIndex
+        );
+
+        match
+            pack_pool.entries
+                .iter_mut()
+                .find(|entry| entry.rarity == card.rarity && entry.index == card.index)
+        {
+            Some(entry) => {
+                entry.remaining = entry.remaining
+                    .checked_add(card.remaining)
+                    .ok_or(NftAuctionError::InvalidCount)?;
+            }
+            None => {
+                require!(pack_pool.entries.len() < MAX_POOL_ENTRIES, NftAuctionError::PackPoolFull);
+                pack_pool.entries.push(*card);
+            }
+        }
+
+        pack_pool.total_remaining = pack_pool.total_remaining
+            .checked_add(card.remaining as u64)
+            .ok_or(NftAuctionError::InvalidCount)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, Mint, Token, TokenAccount };
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        CONFIG_SEED,
+        LEAF_METADATA_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+    },
+    errors::NftAuctionError,
+    events::CardBurned,
+    state::{ CardDefinition, CardSet, CollectionConfig, Config, LeafArgs, LeafMetadata },
+    utils::{ burn_leaf, card_leaf_hashes, BurnLeafAccounts },
+};
+
+#[derive(Accounts)]
+#[instruction(leaf: LeafArgs)]
+pub struct BurnCard<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: Program PDA listed as the verified creator of every card
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(mut, has_one = card_set)]
+    pub card_definition: Box<Account<'info, CardDefinition>>,
+
+    // Only for leaves updated on their own by `update_card_metadata`
+    #[account(
+        seeds = [LEAF_METADATA_SEED, merkle_tree.key().as_ref(), &leaf.nonce.to_le_bytes()],
+        bump = leaf_metadata.bump
+    )]
+    pub leaf_metadata: Option<Account<'info, LeafMetadata>>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    /// Only needed when the card's tier pays a burn reward
+    pub platform_vault: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub vault_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn burn_card<'info>(
+    ctx: Context<'_, '_, '_, 'info, BurnCard<'info>>,
+    leaf: LeafArgs
+) -> Result<()> {
+    // Hashes come from the card definition, so the burned leaf is guaranteed to be this card
+    let (data_hash, creator_hash) = card_leaf_hashes(
+        &ctx.accounts.card_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key(),
+        &leaf,
+        ctx.accounts.leaf_metadata.as_deref()
+    )?;
+
+    burn_leaf(
+        BurnLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.owner,
+            leaf_delegate: &ctx.accounts.leaf_delegate,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &leaf,
+        data_hash,
+        creator_hash,
+        ctx.remaining_accounts
+    )?;
+
+    let card_definition = &mut ctx.accounts.card_definition;
+    card_definition.burned = card_definition.burned
+        .checked_add(1)
+        .ok_or(NftAuctionError::InvalidCount)?;
+
+    let reward = ctx.accounts.card_set.burn_rewards[card_definition.rarity as usize];
+    if reward > 0 {
+        let platform_vault = ctx.accounts.platform_vault
+            .as_ref()
+            .ok_or(NftAuctionError::InvalidPlatformVault)?;
+        require_keys_eq!(
+            platform_vault.key(),
+            ctx.accounts.global_config.platform_vault,
+            NftAuctionError::InvalidPlatformVault
+        );
+
+        let usdc_mint = ctx.accounts.global_config.usdc_mint;
+        let vault_usdc_account = ctx.accounts.vault_usdc_account
+            .as_ref()
+            .ok_or(NftAuctionError::MissingVaultAta)?;
+        require!(
+            vault_usdc_account.mint == usdc_mint &&
+                vault_usdc_account.owner == platform_vault.key(),
+            NftAuctionError::MissingVaultAta
+        );
+
+        let user_usdc_account = ctx.accounts.user_usdc_account
+            .as_ref()
+            .ok_or(NftAuctionError::MissingUserAta)?;
+        require!(
+            user_usdc_account.mint == usdc_mint &&
+                user_usdc_account.owner == ctx.accounts.owner.key(),
+            NftAuctionError::MissingUserAta
+        );
+
+        require!(vault_usdc_account.amount >= reward, NftAuctionError::InsufficientFunds);
+
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), token::Transfer {
+                from: vault_usdc_account.to_account_info(),
+                to: user_usdc_account.to_account_info(),
+                authority: platform_vault.to_account_info(),
+            }),
+            reward
+        )?;
+    }
+
+    emit!(CardBurned {
+        owner: ctx.accounts.owner.key(),
+        card_definition: ctx.accounts.card_definition.key(),
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        nonce: leaf.nonce,
+        reward,
+    });
+
+    Ok(())
+}
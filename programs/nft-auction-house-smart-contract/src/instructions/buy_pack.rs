@@ -1,101 +1,464 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ associated_token::AssociatedToken, token::{ self, Token, TokenAccount } };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ self, Mint, TokenAccount, TokenInterface },
+};
 use orao_solana_vrf::program::OraoVrf;
-use orao_solana_vrf::state::NetworkState;
-use orao_solana_vrf::CONFIG_ACCOUNT_SEED;
-use orao_solana_vrf::RANDOMNESS_ACCOUNT_SEED;
-use crate::{ constants::USDC_MINT, errors::CustomError };   
+use crate::{
+    constants::{
+        BUYBACK_RESERVE_SEED,
+        CARD_SET_SEED,
+        CONFIG_SEED,
+        FEE_SCHEDULE_SEED,
+        MAX_CARDS_PER_PURCHASE,
+        PACK_CONFIG_SEED,
+        PACK_PURCHASE_SEED,
+        PACK_RANDOMNESS_BINDING_SEED,
+        PLAYER_PACK_STATE_SEED,
+        PLAYER_PROFILE_SEED,
+        REFERRAL_POOL_SEED,
+        REFERRER_SEED,
+        SOL_PAYMENT_MINT,
+    },
+    errors::{ CustomError, NftAuctionError },
+    fees::{ distribute_sol, distribute_tokens, FeeRecipients, RecipientAtas, TokenPayment },
+    oracle::{ load_sol_usd_price, usd_to_lamports },
+    randomness::{ binding_seed, request_randomness, RandomnessRequest },
+    state::{
+        BuybackReserve,
+        CardSet,
+        Config,
+        FeeSchedule,
+        PackConfig,
+        PackPool,
+        PackPurchase,
+        PlayerPackState,
+        PlayerProfile,
+        RandomnessBinding,
+        Referrer,
+    },
+};
 
 #[derive(Accounts)]
-#[instruction(force: [u8; 32])]
+#[instruction(force: [u8; 32], pack_id: u8)]
 pub struct BuyPack<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: USDC mint
-    #[account(constraint = usdc_mint.key() == USDC_MINT @ CustomError::InvalidUsdcMint)]
-    pub usdc_mint: AccountInfo<'info>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
 
-    /// CHECK: platfrom vault wallet
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        seeds = [PACK_CONFIG_SEED, card_set.key().as_ref(), &[pack_id]],
+        bump = pack_config.bump,
+        has_one = card_set
+    )]
+    pub pack_config: Box<Account<'info, PackConfig>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PackPurchase::INIT_SPACE,
+        seeds = [PACK_PURCHASE_SEED, &force],
+        bump
+    )]
+    pub pack_purchase: Box<Account<'info, PackPurchase>>,
+
+    // Required when the pack config draws from a finite pool
     #[account(mut)]
-    pub platform_vault: Signer<'info>,
+    pub pack_pool: Option<Box<Account<'info, PackPool>>>,
 
     #[account(
         init_if_needed,
-        payer = platform_vault,
-        associated_token::mint = usdc_mint,
-        associated_token::authority = platform_vault,
-        associated_token::token_program = token_program
+        payer = user,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [PLAYER_PROFILE_SEED, user.key().as_ref()],
+        bump
     )]
-    pub vault_usdc_account: Account<'info, TokenAccount>,
+    pub player_profile: Box<Account<'info, PlayerProfile>>,
 
     #[account(
         init_if_needed,
         payer = user,
-        associated_token::mint = usdc_mint,
-        associated_token::authority = user,
-        associated_token::token_program = token_program
+        space = 8 + PlayerPackState::INIT_SPACE,
+        seeds = [PLAYER_PACK_STATE_SEED, user.key().as_ref(), pack_config.key().as_ref()],
+        bump
+    )]
+    pub player_pack_state: Box<Account<'info, PlayerPackState>>,
+
+    // SPL or Token-2022 mint the pack is paid in; omitted when paying in SOL
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    // Required once the buyer is attributed to a referrer
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, referrer.authority.as_ref()],
+        bump = referrer.bump
     )]
-    pub user_usdc_account: Account<'info, TokenAccount>,
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
 
-    /// CHECK: Treasury
+    /// CHECK: platfrom vault wallet
     #[account(mut)]
-    pub treasury: AccountInfo<'info>,
+    pub platform_vault: Signer<'info>,
+
+    #[account(mut, seeds = [BUYBACK_RESERVE_SEED], bump = buyback_reserve.bump)]
+    pub buyback_reserve: Box<Account<'info, BuybackReserve>>,
 
-    /// CHECK: Randomness
+    // Reserve's USDC account, required for USDC payments
     #[account(
         mut,
-        seeds = [RANDOMNESS_ACCOUNT_SEED, &force],
-        bump,
-        seeds::program = orao_solana_vrf::ID
+        associated_token::mint = global_config.usdc_mint,
+        associated_token::authority = buyback_reserve,
+        associated_token::token_program = token_program
     )]
+    pub reserve_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Pyth SOL/USD `PriceUpdateV2`, needed for oracle-priced SOL payments
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [FEE_SCHEDULE_SEED], bump = fee_schedule.bump)]
+    pub fee_schedule: Box<Account<'info, FeeSchedule>>,
+
+    /// CHECK: Fee schedule treasury wallet
+    #[account(mut, address = fee_schedule.treasury)]
+    pub treasury_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Fee schedule creator wallet
+    #[account(mut, address = fee_schedule.creator)]
+    pub creator_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Fee schedule partner wallet
+    #[account(mut, address = fee_schedule.partner)]
+    pub partner_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA holding the referral share until it is claimed
+    #[account(mut, seeds = [REFERRAL_POOL_SEED], bump)]
+    pub referral_pool: UncheckedAccount<'info>,
+
+    // ATAs of the payment mint for each fee recipient, created if missing; omitted for SOL
+    /// CHECK: Checked against the treasury wallet's ATA
+    #[account(mut)]
+    pub treasury_payment_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Checked against the creator wallet's ATA
+    #[account(mut)]
+    pub creator_payment_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Checked against the partner wallet's ATA
+    #[account(mut)]
+    pub partner_payment_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Checked against the referral pool's ATA
+    #[account(mut)]
+    pub referral_payment_account: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub user_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: ORAO treasury, only needed for ORAO packs; checked against the network state
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Randomness account of the pack config's provider, validated by the provider.
+    /// Passed writable for ORAO and the mock only; the SlotHashes sysvar can't be writable.
     pub random: AccountInfo<'info>,
 
     #[account(
-        mut,
-        seeds = [CONFIG_ACCOUNT_SEED],
-        bump,
-        seeds::program = orao_solana_vrf::ID
+        init_if_needed,
+        payer = user,
+        space = 8 + RandomnessBinding::INIT_SPACE,
+        seeds = [
+            PACK_RANDOMNESS_BINDING_SEED,
+            random.key().as_ref(),
+            binding_seed(random.key, &force),
+        ],
+        bump
     )]
-    pub config: Account<'info, NetworkState>,
+    pub randomness_binding: Box<Account<'info, RandomnessBinding>>,
 
-    pub vrf: Program<'info, OraoVrf>,
+    /// CHECK: ORAO network state, validated by the provider
+    #[account(mut)]
+    pub config: Option<UncheckedAccount<'info>>,
+
+    pub vrf: Option<Program<'info, OraoVrf>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn buy_pack(ctx: Context<BuyPack>, force: [u8; 32], pack_id: u8, pack_count: u8) -> Result<()> {
-    let pack_price = match pack_id {
-        0..=5 => crate::constants::PACK_PRICES[pack_id as usize],
-        _ => {
-            return Err(CustomError::InvalidPackId.into());
-        }
+pub fn buy_pack(
+    ctx: Context<BuyPack>,
+    force: [u8; 32],
+    _pack_id: u8,
+    pack_count: u8,
+    max_payment: Option<u64>
+) -> Result<()> {
+    let pack_config = &ctx.accounts.pack_config;
+    require!(pack_config.drop_table != Pubkey::default(), NftAuctionError::NoActiveDropTable);
+
+    let card_count = (pack_count as usize) * (pack_config.cards_per_pack as usize);
+    require!(
+        pack_count > 0 && card_count <= MAX_CARDS_PER_PURCHASE,
+        NftAuctionError::InvalidCount
+    );
+
+    let player_profile = &mut ctx.accounts.player_profile;
+    if player_profile.user == Pubkey::default() {
+        player_profile.version = PlayerProfile::VERSION;
+        player_profile.user = ctx.accounts.user.key();
+        player_profile.bump = ctx.bumps.player_profile;
+    }
+
+    let player_pack_state = &mut ctx.accounts.player_pack_state;
+    if player_pack_state.user == Pubkey::default() {
+        player_pack_state.version = PlayerPackState::VERSION;
+        player_pack_state.user = ctx.accounts.user.key();
+        player_pack_state.pack_config = pack_config.key();
+        player_pack_state.bump = ctx.bumps.player_pack_state;
+    }
+
+    let clock = Clock::get()?;
+    player_pack_state.record_purchase(
+        &pack_config.purchase_limits,
+        pack_count,
+        clock.unix_timestamp,
+        clock.slot
+    )?;
+    let sequence = player_pack_state.purchases;
+    player_pack_state.purchases = sequence.saturating_add(1);
+
+    if pack_config.pack_pool != Pubkey::default() {
+        let pack_pool = ctx.accounts.pack_pool.as_mut().ok_or(NftAuctionError::InvalidPackPool)?;
+        require_keys_eq!(pack_pool.key(), pack_config.pack_pool, NftAuctionError::InvalidPackPool);
+        require!(
+            pack_pool.available() >= (card_count as u64),
+            NftAuctionError::PackPoolExhausted
+        );
+        pack_pool.reserved += card_count as u64;
+    }
+
+    // Prices are in base units of the payment mint, lamports for SOL
+    let payment_mint = ctx.accounts.payment_mint
+        .as_ref()
+        .map(|mint| mint.key())
+        .unwrap_or(SOL_PAYMENT_MINT);
+    let sol_oracle_priced =
+        payment_mint == SOL_PAYMENT_MINT &&
+        !pack_config.payment_prices.iter().any(|p| p.mint == SOL_PAYMENT_MINT);
+    let buy_amount = if sol_oracle_priced {
+        let price_update = ctx.accounts.price_update
+            .as_ref()
+            .ok_or(NftAuctionError::InvalidPriceAccount)?;
+        let usd_amount = pack_config.price
+            .checked_mul(pack_count as u64)
+            .ok_or(NftAuctionError::InvalidCount)?;
+        usd_to_lamports(usd_amount, &load_sol_usd_price(price_update)?)?
+    } else {
+        pack_config
+            .price_for(payment_mint, ctx.accounts.global_config.usdc_mint)?
+            .checked_mul(pack_count as u64)
+            .ok_or(NftAuctionError::InvalidCount)?
     };
-    let buy_amount = (pack_price as u64) * (pack_count as u64) * 1_000_000; // USDC has 6 decimals
 
-    require!(ctx.accounts.user_usdc_account.amount >= buy_amount, CustomError::InsufficientFunds);
+    // Bound chosen by the buyer, mostly against oracle price moves before landing
+    if let Some(max_payment) = max_payment {
+        require!(buy_amount <= max_payment, NftAuctionError::SlippageExceeded);
+    }
 
-    token::transfer(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), token::Transfer {
-            from: ctx.accounts.user_usdc_account.to_account_info(),
-            to: ctx.accounts.vault_usdc_account.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
+    // Part of every USDC payment goes to the buyback reserve before fees are split
+    let top_up = if payment_mint == ctx.accounts.global_config.usdc_mint {
+        ctx.accounts.buyback_reserve.top_up_amount(buy_amount)
+    } else {
+        0
+    };
+    let mut split = ctx.accounts.fee_schedule.split(buy_amount - top_up);
+
+    // The buyer's referrer is locked in on the first referred purchase
+    let player_profile = &mut ctx.accounts.player_profile;
+    match (player_profile.referrer, ctx.accounts.referrer.as_mut()) {
+        (Some(locked), Some(referrer)) => {
+            require_keys_eq!(referrer.key(), locked, NftAuctionError::ReferrerLocked);
+        }
+        (Some(_), None) => {
+            return err!(NftAuctionError::ReferrerLocked);
+        }
+        (None, Some(referrer)) => {
+            require_keys_neq!(
+                referrer.authority,
+                ctx.accounts.user.key(),
+                NftAuctionError::SelfReferral
+            );
+            player_profile.referrer = Some(referrer.key());
+        }
+        (None, None) => {}
+    }
+
+    // Without a referrer to credit, the referral share goes to the treasury instead of the pool
+    if ctx.accounts.referrer.is_none() {
+        split.treasury += split.referral;
+        split.referral = 0;
+    }
+
+    match &ctx.accounts.payment_mint {
+        None => {
+            require!(ctx.accounts.user.lamports() >= buy_amount, CustomError::InsufficientFunds);
+
+            distribute_sol(
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &(FeeRecipients {
+                    treasury: ctx.accounts.treasury_wallet.to_account_info(),
+                    creator: ctx.accounts.creator_wallet.to_account_info(),
+                    partner: ctx.accounts.partner_wallet.to_account_info(),
+                    referral_pool: ctx.accounts.referral_pool.to_account_info(),
+                }),
+                &split
+            )?;
+        }
+        Some(mint) => {
+            require_keys_eq!(
+                *mint.to_account_info().owner,
+                ctx.accounts.token_program.key(),
+                NftAuctionError::InvalidPaymentAccount
+            );
+
+            let user_payment_account = ctx.accounts.user_payment_account
+                .as_ref()
+                .ok_or(NftAuctionError::MissingUserAta)?;
+            require!(
+                user_payment_account.mint == mint.key() &&
+                    user_payment_account.owner == ctx.accounts.user.key(),
+                NftAuctionError::InvalidPaymentAccount
+            );
+            require!(user_payment_account.amount >= buy_amount, CustomError::InsufficientFunds);
+
+            let atas = RecipientAtas {
+                mint: mint.to_account_info(),
+                payer: ctx.accounts.platform_vault.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            };
+            let recipients = FeeRecipients {
+                treasury: atas.resolve(
+                    ctx.accounts.treasury_payment_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.treasury_wallet.to_account_info()
+                )?,
+                creator: atas.resolve(
+                    ctx.accounts.creator_payment_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.creator_wallet.to_account_info()
+                )?,
+                partner: atas.resolve(
+                    ctx.accounts.partner_payment_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.partner_wallet.to_account_info()
+                )?,
+                referral_pool: atas.resolve(
+                    ctx.accounts.referral_payment_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.referral_pool.to_account_info()
+                )?,
+            };
+
+            distribute_tokens(
+                &(TokenPayment {
+                    from: user_payment_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                    mint: mint.to_account_info(),
+                    decimals: mint.decimals,
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                }),
+                &recipients,
+                &split
+            )?;
+
+            if top_up > 0 {
+                let reserve_token_account = ctx.accounts.reserve_token_account
+                    .as_ref()
+                    .ok_or(NftAuctionError::MissingVaultAta)?;
+                require_keys_eq!(
+                    reserve_token_account.mint,
+                    mint.key(),
+                    NftAuctionError::InvalidPaymentAccount
+                );
+
+                token_interface::transfer_checked(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: user_payment_account.to_account_info(),
+                            mint: mint.to_account_info(),
+                            to: reserve_token_account.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        }
+                    ),
+                    top_up,
+                    mint.decimals
+                )?;
+
+                let reserve_balance = reserve_token_account.amount + top_up;
+                ctx.accounts.buyback_reserve.refresh(reserve_balance);
+            }
+        }
+    }
+
+    // Every card drawn can later be sold back through `instant_sell`. SOL and other-mint payments
+    // top up nothing, so they dilute the reserve ratio and are absorbed by `scaled_buyback`
+    let buyback_liability = ctx.accounts.global_config
+        .buyback_price(ctx.accounts.pack_config.price)
+        .saturating_mul(card_count as u64);
+    ctx.accounts.buyback_reserve.add_liability(buyback_liability);
+
+    let randomness_provider = ctx.accounts.pack_config.randomness_provider;
+    let commit_slot = request_randomness(
+        randomness_provider,
+        &(RandomnessRequest {
+            payer: &ctx.accounts.user.to_account_info(),
+            random: &ctx.accounts.random,
+            network_state: ctx.accounts.config.as_ref().map(|config| config.to_account_info()),
+            treasury: ctx.accounts.treasury.as_ref().map(|treasury| treasury.to_account_info()),
+            vrf_program: ctx.accounts.vrf.as_ref().map(|vrf| vrf.to_account_info()),
+            system_program: &ctx.accounts.system_program.to_account_info(),
         }),
-        buy_amount
+        force
     )?;
 
-    let cpi_program = ctx.accounts.vrf.to_account_info();
-    let cpi_accounts = orao_solana_vrf::cpi::accounts::RequestV2 {
-        payer: ctx.accounts.user.to_account_info(),
-        network_state: ctx.accounts.config.to_account_info(),
-        treasury: ctx.accounts.treasury.to_account_info(),
-        request: ctx.accounts.random.to_account_info(),
-        system_program: ctx.accounts.system_program.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    orao_solana_vrf::cpi::request_v2(cpi_ctx, force)?;
+    let pack_purchase = &mut ctx.accounts.pack_purchase;
+    pack_purchase.user = ctx.accounts.user.key();
+    pack_purchase.card_set = ctx.accounts.card_set.key();
+    pack_purchase.pack_config = ctx.accounts.pack_config.key();
+    pack_purchase.force = force;
+    pack_purchase.pack_count = pack_count;
+    pack_purchase.cards_per_pack = ctx.accounts.pack_config.cards_per_pack;
+    pack_purchase.drop_table = ctx.accounts.pack_config.drop_table;
+    pack_purchase.drop_table_version = ctx.accounts.pack_config.drop_table_version;
+    pack_purchase.pack_pool = ctx.accounts.pack_config.pack_pool;
+    pack_purchase.randomness_provider = randomness_provider;
+    pack_purchase.randomness = ctx.accounts.random.key();
+    pack_purchase.request_seed = force;
+    pack_purchase.commit_slot = commit_slot;
+    pack_purchase.sequence = sequence;
+    pack_purchase.pity_before = 0;
+    pack_purchase.revealed = false;
+    pack_purchase.cards = Vec::new();
+    pack_purchase.version = PackPurchase::VERSION;
+    pack_purchase.bump = ctx.bumps.pack_purchase;
+
+    ctx.accounts.randomness_binding.bind(
+        ctx.accounts.random.key(),
+        ctx.accounts.pack_purchase.key(),
+        ctx.bumps.randomness_binding
+    )?;
+
+    if let Some(referrer) = ctx.accounts.referrer.as_mut() {
+        referrer.credit(payment_mint, split.referral)?;
+        referrer.referred_purchases = referrer.referred_purchases.saturating_add(1);
+    }
 
     Ok(())
 }
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ ROLES_SEED, WITHDRAWAL_REQUEST_SEED },
+    errors::NftAuctionError,
+    state::{ Role, Roles, WithdrawalRequest },
+};
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(guardian.key, Role::Guardian) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        close = guardian,
+        seeds = [WITHDRAWAL_REQUEST_SEED, &withdrawal_request.id.to_le_bytes()],
+        bump = withdrawal_request.bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+}
+
+pub fn cancel_withdrawal(_ctx: Context<CancelWithdrawal>) -> Result<()> {
+    Ok(())
+}
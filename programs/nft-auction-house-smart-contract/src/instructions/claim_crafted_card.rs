@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        BUBBLEGUM_SIGNER_SEED,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        CRAFT_REQUEST_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+        TOKEN_METADATA_PROGRAM_ID,
+    },
+    errors::NftAuctionError,
+    state::{ CardDefinition, CardSet, CollectionConfig, CraftRequest },
+    utils::{ card_metadata_args, mint_card_leaf, MintLeafAccounts },
+};
+
+#[derive(Accounts)]
+#[instruction(force: [u8; 32])]
+pub struct ClaimCraftedCard<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        mut,
+        seeds = [CRAFT_REQUEST_SEED, &force],
+        bump = craft_request.bump,
+        has_one = user,
+        has_one = card_set
+    )]
+    pub craft_request: Box<Account<'info, CraftRequest>>,
+
+    #[account(mut, has_one = card_set)]
+    pub card_definition: Box<Account<'info, CardDefinition>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    /// CHECK: Program PDA that owns the collection; also the delegate of every card set tree
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Collection metadata
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        mut,
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum collection CPI signer
+    #[account(
+        seeds = [BUBBLEGUM_SIGNER_SEED],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub bubblegum_signer: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Token Metadata program
+    #[account(constraint = token_metadata_program.key() == TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Mints the card drawn by `fulfill_craft` to the crafter; each request can be claimed once
+pub fn claim_crafted_card(ctx: Context<ClaimCraftedCard>, _force: [u8; 32]) -> Result<()> {
+    let card = ctx.accounts.craft_request.card.take().ok_or(NftAuctionError::NoCardToClaim)?;
+    require!(
+        ctx.accounts.card_definition.rarity == card.rarity &&
+            ctx.accounts.card_definition.index == card.index,
+        NftAuctionError::CardDefinitionMismatch
+    );
+
+    let metadata = card_metadata_args(
+        &ctx.accounts.card_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key()
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[COLLECTION_AUTHORITY_SEED, &[ctx.bumps.collection_authority]],
+    ];
+
+    // The collection authority signs as tree delegate, so no admin is needed to deliver the card
+    mint_card_leaf(
+        MintLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.user,
+            leaf_delegate: &ctx.accounts.user,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            payer: &ctx.accounts.user,
+            tree_delegate: &ctx.accounts.collection_authority,
+            collection_authority: &ctx.accounts.collection_authority,
+            bubblegum_program: &ctx.accounts.bubblegum_program,
+            collection_mint: &ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: &ctx.accounts.collection_metadata,
+            collection_master_edition: &ctx.accounts.collection_master_edition,
+            bubblegum_signer: &ctx.accounts.bubblegum_signer,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            token_metadata_program: &ctx.accounts.token_metadata_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &metadata,
+        signer_seeds
+    )?;
+
+    let card_definition = &mut ctx.accounts.card_definition;
+    card_definition.minted = card_definition.minted
+        .checked_add(1)
+        .ok_or(NftAuctionError::InvalidCount)?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface };
+use crate::{
+    constants::{ REFERRAL_POOL_SEED, REFERRER_SEED, SOL_PAYMENT_MINT },
+    errors::NftAuctionError,
+    state::Referrer,
+};
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, authority.key().as_ref()],
+        bump = referrer.bump,
+        has_one = authority
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    /// CHECK: Program PDA holding unclaimed referral shares
+    #[account(mut, seeds = [REFERRAL_POOL_SEED], bump)]
+    pub referral_pool: UncheckedAccount<'info>,
+
+    // Omitted to claim the SOL balance
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = referral_pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub authority_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+    let mint = ctx.accounts.payment_mint
+        .as_ref()
+        .map(|mint| mint.key())
+        .unwrap_or(SOL_PAYMENT_MINT);
+    let amount = ctx.accounts.referrer.take(mint)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[REFERRAL_POOL_SEED, &[ctx.bumps.referral_pool]]];
+
+    match &ctx.accounts.payment_mint {
+        None => {
+            // The pool is a plain system account and must stay rent exempt
+            let rent_floor = Rent::get()?.minimum_balance(0);
+            require!(
+                ctx.accounts.referral_pool.lamports().saturating_sub(amount) >= rent_floor,
+                NftAuctionError::InsufficientFunds
+            );
+
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.referral_pool.to_account_info(),
+                        to: ctx.accounts.authority.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                amount
+            )?;
+        }
+        Some(mint) => {
+            let pool_token_account = ctx.accounts.pool_token_account
+                .as_ref()
+                .ok_or(NftAuctionError::MissingVaultAta)?;
+            let authority_token_account = ctx.accounts.authority_token_account
+                .as_ref()
+                .ok_or(NftAuctionError::MissingUserAta)?;
+            let token_program = ctx.accounts.token_program
+                .as_ref()
+                .ok_or(NftAuctionError::InvalidPaymentAccount)?;
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: pool_token_account.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: authority_token_account.to_account_info(),
+                        authority: ctx.accounts.referral_pool.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                amount,
+                mint.decimals
+            )?;
+        }
+    }
+
+    Ok(())
+}
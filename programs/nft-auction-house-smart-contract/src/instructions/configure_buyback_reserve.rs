@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ BUYBACK_RESERVE_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ BuybackReserve, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct ConfigureBuybackReserve<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + BuybackReserve::INIT_SPACE,
+        seeds = [BUYBACK_RESERVE_SEED],
+        bump
+    )]
+    pub buyback_reserve: Account<'info, BuybackReserve>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PauseBuybacks<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(guardian.key, Role::Guardian) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(mut, seeds = [BUYBACK_RESERVE_SEED], bump = buyback_reserve.bump)]
+    pub buyback_reserve: Account<'info, BuybackReserve>,
+}
+
+pub fn configure_buyback_reserve(
+    ctx: Context<ConfigureBuybackReserve>,
+    top_up_bps: u16,
+    target_ratio_bps: u16,
+    pause_ratio_bps: u16,
+    paused: bool
+) -> Result<()> {
+    require!(
+        top_up_bps <= 10000 && pause_ratio_bps <= target_ratio_bps,
+        NftAuctionError::InvalidReserveRatio
+    );
+
+    let buyback_reserve = &mut ctx.accounts.buyback_reserve;
+    buyback_reserve.top_up_bps = top_up_bps;
+    buyback_reserve.target_ratio_bps = target_ratio_bps;
+    buyback_reserve.pause_ratio_bps = pause_ratio_bps;
+    buyback_reserve.paused = paused;
+    buyback_reserve.version = BuybackReserve::VERSION;
+    buyback_reserve.bump = ctx.bumps.buyback_reserve;
+
+    let reserve_balance = buyback_reserve.reserve_balance;
+    buyback_reserve.refresh(reserve_balance);
+
+    Ok(())
+}
+
+pub fn set_buybacks_paused(ctx: Context<PauseBuybacks>, paused: bool) -> Result<()> {
+    ctx.accounts.buyback_reserve.paused = paused;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use orao_solana_vrf::program::OraoVrf;
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        CRAFT_RANDOMNESS_BINDING_SEED,
+        CRAFT_REQUEST_SEED,
+        RARITY_TIERS,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+    },
+    errors::NftAuctionError,
+    randomness::{ binding_seed, request_randomness, RandomnessRequest },
+    state::{
+        CardDefinition,
+        CardSet,
+        CollectionConfig,
+        CraftRequest,
+        LeafArgs,
+        RandomnessBinding,
+        RandomnessProvider,
+    },
+    utils::{ burn_leaf, card_leaf_hashes, load_leaf_metadata, BurnLeafAccounts },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CraftInput {
+    pub leaf: LeafArgs,
+    pub proof_len: u8,
+    // Whether the leaf's metadata record follows its delegate
+    pub leaf_metadata: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(force: [u8; 32])]
+pub struct Craft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Program PDA listed as the verified creator of every card
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + CraftRequest::INIT_SPACE,
+        seeds = [CRAFT_REQUEST_SEED, &force],
+        bump
+    )]
+    pub craft_request: Box<Account<'info, CraftRequest>>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    /// CHECK: ORAO treasury, checked against the network state
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Randomness account, validated by the provider
+    #[account(mut)]
+    pub random: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + RandomnessBinding::INIT_SPACE,
+        seeds = [
+            CRAFT_RANDOMNESS_BINDING_SEED,
+            random.key().as_ref(),
+            binding_seed(random.key, &force),
+        ],
+        bump
+    )]
+    pub randomness_binding: Box<Account<'info, RandomnessBinding>>,
+
+    /// CHECK: ORAO network state, validated by the provider
+    #[account(mut)]
+    pub config: Option<UncheckedAccount<'info>>,
+
+    pub vrf: Option<Program<'info, OraoVrf>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts hold, per input: its card definition, its leaf delegate, its leaf metadata
+// record when flagged, then `proof_len` proof nodes
+pub fn craft<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Craft<'info>>,
+    force: [u8; 32],
+    inputs: Vec<CraftInput>
+) -> Result<()> {
+    let card_set = &ctx.accounts.card_set;
+    require!(card_set.craft_input_count > 0, NftAuctionError::CraftingDisabled);
+    require!(
+        inputs.len() == (card_set.craft_input_count as usize),
+        NftAuctionError::InvalidCraftInputs
+    );
+
+    let mut remaining = ctx.remaining_accounts;
+    let mut input_rarity: Option<u8> = None;
+
+    for input in inputs.iter() {
+        let proof_start = if input.leaf_metadata { 3 } else { 2 };
+        let proof_end = proof_start + (input.proof_len as usize);
+        require!(remaining.len() >= proof_end, NftAuctionError::InvalidCraftInputs);
+
+        let mut card_definition: Account<'info, CardDefinition> = Account::try_from(&remaining[0])?;
+        require_keys_eq!(card_definition.card_set, card_set.key(), NftAuctionError::InvalidCraftInputs);
+
+        match input_rarity {
+            None => {
+                input_rarity = Some(card_definition.rarity);
+            }
+            Some(rarity) => {
+                require!(card_definition.rarity == rarity, NftAuctionError::InvalidCraftInputs);
+            }
+        }
+
+        let leaf_metadata = if input.leaf_metadata {
+            let merkle_tree = ctx.accounts.merkle_tree.key();
+            Some(load_leaf_metadata(&remaining[2], &merkle_tree, input.leaf.nonce)?)
+        } else {
+            None
+        };
+        let (data_hash, creator_hash) = card_leaf_hashes(
+            &card_definition,
+            &ctx.accounts.collection_config,
+            ctx.accounts.collection_authority.key(),
+            &input.leaf,
+            leaf_metadata.as_ref()
+        )?;
+
+        burn_leaf(
+            BurnLeafAccounts {
+                tree_authority: &ctx.accounts.tree_authority,
+                leaf_owner: &ctx.accounts.user,
+                leaf_delegate: &remaining[1],
+                merkle_tree: &ctx.accounts.merkle_tree,
+                log_wrapper: &ctx.accounts.log_wrapper,
+                compression_program: &ctx.accounts.compression_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            &input.leaf,
+            data_hash,
+            creator_hash,
+            &remaining[proof_start..proof_end]
+        )?;
+
+        card_definition.burned = card_definition.burned
+            .checked_add(1)
+            .ok_or(NftAuctionError::InvalidCount)?;
+        card_definition.exit(&crate::ID)?;
+
+        remaining = &remaining[proof_end..];
+    }
+
+    let input_rarity = input_rarity.ok_or(NftAuctionError::InvalidCraftInputs)?;
+    require!((input_rarity as usize) + 1 < RARITY_TIERS, NftAuctionError::InvalidCraftInputs);
+
+    // Crafting always uses ORAO; the commit slot only matters for the other providers
+    request_randomness(
+        RandomnessProvider::Orao,
+        &(RandomnessRequest {
+            payer: &ctx.accounts.user.to_account_info(),
+            random: &ctx.accounts.random,
+            network_state: ctx.accounts.config.as_ref().map(|config| config.to_account_info()),
+            treasury: ctx.accounts.treasury.as_ref().map(|treasury| treasury.to_account_info()),
+            vrf_program: ctx.accounts.vrf.as_ref().map(|vrf| vrf.to_account_info()),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        }),
+        force
+    )?;
+
+    let craft_request = &mut ctx.accounts.craft_request;
+    craft_request.user = ctx.accounts.user.key();
+    craft_request.card_set = ctx.accounts.card_set.key();
+    craft_request.target_rarity = input_rarity + 1;
+    craft_request.force = force;
+    craft_request.randomness = ctx.accounts.random.key();
+    craft_request.revealed = false;
+    craft_request.card = None;
+    craft_request.version = CraftRequest::VERSION;
+    craft_request.bump = ctx.bumps.craft_request;
+
+    ctx.accounts.randomness_binding.bind(
+        ctx.accounts.random.key(),
+        ctx.accounts.craft_request.key(),
+        ctx.bumps.randomness_binding
+    )?;
+
+    Ok(())
+}
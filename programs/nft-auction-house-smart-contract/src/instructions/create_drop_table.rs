@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, DROP_TABLE_SEED, PACK_CONFIG_SEED, RARITY_TIERS, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, DropTable, PackConfig, Role, Roles },
+};
+
+#[derive(Accounts)]
+#[instruction(version: u32)]
+pub struct CreateDropTable<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(
+        seeds = [PACK_CONFIG_SEED, card_set.key().as_ref(), &[pack_config.pack_id]],
+        bump = pack_config.bump,
+        has_one = card_set
+    )]
+    pub pack_config: Account<'info, PackConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + DropTable::INIT_SPACE,
+        seeds = [DROP_TABLE_SEED, pack_config.key().as_ref(), &version.to_le_bytes()],
+        bump
+    )]
+    pub drop_table: Account<'info, DropTable>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn validate_drop_weights(
+    rarity_weights: &[u16; RARITY_TIERS],
+    pity_rarity: u8
+) -> Result<()> {
+    require!(
+        rarity_weights.iter().any(|w| *w > 0),
+        NftAuctionError::InvalidRarityWeights
+    );
+    require!((pity_rarity as usize) < RARITY_TIERS, NftAuctionError::InvalidRarity);
+    Ok(())
+}
+
+pub fn create_drop_table(
+    ctx: Context<CreateDropTable>,
+    version: u32,
+    rarity_weights: [u16; RARITY_TIERS],
+    pity_threshold: u16,
+    pity_rarity: u8
+) -> Result<()> {
+    require!(
+        version > ctx.accounts.pack_config.drop_table_version,
+        NftAuctionError::StaleDropTableVersion
+    );
+    validate_drop_weights(&rarity_weights, pity_rarity)?;
+
+    let drop_table = &mut ctx.accounts.drop_table;
+    drop_table.pack_config = ctx.accounts.pack_config.key();
+    drop_table.version = version;
+    drop_table.rarity_weights = rarity_weights;
+    drop_table.tier_sizes = Default::default();
+    drop_table.pity_threshold = pity_threshold;
+    drop_table.pity_rarity = pity_rarity;
+    drop_table.activated = false;
+    drop_table.activated_at = 0;
+    drop_table.hash = [0u8; 32];
+    drop_table.layout_version = DropTable::LAYOUT_VERSION;
+    drop_table.bump = ctx.bumps.drop_table;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, PACK_CONFIG_SEED, PACK_POOL_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, PackConfig, PackPool, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct CreatePackPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(
+        mut,
+        seeds = [PACK_CONFIG_SEED, card_set.key().as_ref(), &[pack_config.pack_id]],
+        bump = pack_config.bump,
+        has_one = card_set
+    )]
+    pub pack_config: Account<'info, PackConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PackPool::INIT_SPACE,
+        seeds = [PACK_POOL_SEED, pack_config.key().as_ref()],
+        bump
+    )]
+    pub pack_pool: Account<'info, PackPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// From now on packs of this config draw from the pool instead of the drop table weights
+pub fn create_pack_pool(ctx: Context<CreatePackPool>) -> Result<()> {
+    let pack_pool = &mut ctx.accounts.pack_pool;
+    pack_pool.pack_config = ctx.accounts.pack_config.key();
+    pack_pool.entries = Vec::new();
+    pack_pool.total_remaining = 0;
+    pack_pool.reserved = 0;
+    pack_pool.version = PackPool::VERSION;
+    pack_pool.bump = ctx.bumps.pack_pool;
+
+    ctx.accounts.pack_config.pack_pool = pack_pool.key();
+
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, Mint, Token, TokenAccount };
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        CONFIG_SEED,
+        EVOLUTION_RECORD_SEED,
+        EVOLUTION_STAKE_SEED,
+        LEAF_METADATA_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+    },
+    errors::{ CustomError, NftAuctionError },
+    events::CardEvolved,
+    state::{
+        CardDefinition,
+        CardSet,
+        CollectionConfig,
+        Config,
+        EvolutionCost,
+        EvolutionRecord,
+        EvolutionStake,
+        LeafArgs,
+        LeafMetadata,
+    },
+    utils::{ burn_leaf, card_leaf_hashes, load_leaf_metadata, BurnLeafAccounts },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EvolveArgs {
+    pub leaf: LeafArgs,
+    pub proof_len: u8,
+    // Second leaf of the same card, required when the cost is `Duplicate`
+    pub duplicate: Option<LeafArgs>,
+    // Whether the duplicate's leaf metadata record follows its delegate
+    pub duplicate_leaf_metadata: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(args: EvolveArgs)]
+pub struct Evolve<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: Program PDA listed as the verified creator of every card
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        mut,
+        has_one = card_set,
+        constraint = source_definition.evolves_into == Some(target_definition.key()) @ NftAuctionError::InvalidEvolutionTarget
+    )]
+    pub source_definition: Box<Account<'info, CardDefinition>>,
+
+    #[account(has_one = card_set)]
+    pub target_definition: Box<Account<'info, CardDefinition>>,
+
+    // Only for a source leaf updated on its own by `update_card_metadata`
+    #[account(
+        seeds = [LEAF_METADATA_SEED, merkle_tree.key().as_ref(), &args.leaf.nonce.to_le_bytes()],
+        bump = leaf_metadata.bump
+    )]
+    pub leaf_metadata: Option<Account<'info, LeafMetadata>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + EvolutionRecord::INIT_SPACE,
+        seeds = [EVOLUTION_RECORD_SEED, merkle_tree.key().as_ref(), &args.leaf.nonce.to_le_bytes()],
+        bump
+    )]
+    pub evolution_record: Box<Account<'info, EvolutionRecord>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            EVOLUTION_STAKE_SEED,
+            owner.key().as_ref(),
+            merkle_tree.key().as_ref(),
+            &args.leaf.nonce.to_le_bytes(),
+        ],
+        bump = evolution_stake.bump
+    )]
+    pub evolution_stake: Option<Box<Account<'info, EvolutionStake>>>,
+
+    #[account(mut)]
+    pub user_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub vault_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts: source proof nodes, then (for `Duplicate`) the duplicate's delegate, its
+// leaf metadata record when flagged, and its proof
+pub fn evolve<'info>(
+    ctx: Context<'_, '_, '_, 'info, Evolve<'info>>,
+    args: EvolveArgs
+) -> Result<()> {
+    let (data_hash, creator_hash) = card_leaf_hashes(
+        &ctx.accounts.source_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key(),
+        &args.leaf,
+        ctx.accounts.leaf_metadata.as_deref()
+    )?;
+
+    let proof_len = args.proof_len as usize;
+    require!(ctx.remaining_accounts.len() >= proof_len, NftAuctionError::EvolutionCostMissing);
+    let (source_proof, extra_accounts) = ctx.remaining_accounts.split_at(proof_len);
+
+    let cost = ctx.accounts.source_definition.evolution_cost;
+    let mut burned = 1u64;
+
+    match cost {
+        EvolutionCost::Free => {}
+        EvolutionCost::Usdc { amount } => {
+            let user_usdc_account = ctx.accounts.user_usdc_account
+                .as_ref()
+                .ok_or(NftAuctionError::MissingUserAta)?;
+            let vault_usdc_account = ctx.accounts.vault_usdc_account
+                .as_ref()
+                .ok_or(NftAuctionError::MissingVaultAta)?;
+            require!(
+                vault_usdc_account.mint == ctx.accounts.global_config.usdc_mint &&
+                    vault_usdc_account.owner == ctx.accounts.global_config.platform_vault,
+                NftAuctionError::MissingVaultAta
+            );
+            require!(user_usdc_account.amount >= amount, CustomError::InsufficientFunds);
+
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), token::Transfer {
+                    from: user_usdc_account.to_account_info(),
+                    to: vault_usdc_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                }),
+                amount
+            )?;
+        }
+        EvolutionCost::Duplicate => {
+            let duplicate = args.duplicate.as_ref().ok_or(NftAuctionError::EvolutionCostMissing)?;
+            require!(duplicate.nonce != args.leaf.nonce, NftAuctionError::EvolutionCostMissing);
+            let (duplicate_delegate, duplicate_accounts) = extra_accounts
+                .split_first()
+                .ok_or(NftAuctionError::EvolutionCostMissing)?;
+            let (duplicate_record, duplicate_proof) = if args.duplicate_leaf_metadata {
+                let (record, proof) = duplicate_accounts
+                    .split_first()
+                    .ok_or(NftAuctionError::EvolutionCostMissing)?;
+                let record = load_leaf_metadata(
+                    record,
+                    &ctx.accounts.merkle_tree.key(),
+                    duplicate.nonce
+                )?;
+                (Some(record), proof)
+            } else {
+                (None, duplicate_accounts)
+            };
+            let (duplicate_data_hash, _) = card_leaf_hashes(
+                &ctx.accounts.source_definition,
+                &ctx.accounts.collection_config,
+                ctx.accounts.collection_authority.key(),
+                duplicate,
+                duplicate_record.as_ref()
+            )?;
+
+            burn_leaf(
+                BurnLeafAccounts {
+                    tree_authority: &ctx.accounts.tree_authority,
+                    leaf_owner: &ctx.accounts.owner,
+                    leaf_delegate: duplicate_delegate,
+                    merkle_tree: &ctx.accounts.merkle_tree,
+                    log_wrapper: &ctx.accounts.log_wrapper,
+                    compression_program: &ctx.accounts.compression_program,
+                    system_program: &ctx.accounts.system_program,
+                },
+                duplicate,
+                duplicate_data_hash,
+                creator_hash,
+                duplicate_proof
+            )?;
+            burned += 1;
+        }
+        EvolutionCost::Staking { duration } => {
+            let evolution_stake = ctx.accounts.evolution_stake
+                .as_ref()
+                .ok_or(NftAuctionError::EvolutionCostMissing)?;
+            require_keys_eq!(
+                evolution_stake.card_definition,
+                ctx.accounts.source_definition.key(),
+                NftAuctionError::EvolutionCostMissing
+            );
+            // Still delegated to the stake, so the leaf hasn't changed hands since staking
+            require_keys_eq!(
+                ctx.accounts.leaf_delegate.key(),
+                evolution_stake.key(),
+                NftAuctionError::LeafNotStaked
+            );
+
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                now.saturating_sub(evolution_stake.started_at) >= duration,
+                NftAuctionError::EvolutionNotReady
+            );
+        }
+    }
+
+    burn_leaf(
+        BurnLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.owner,
+            leaf_delegate: &ctx.accounts.leaf_delegate,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &args.leaf,
+        data_hash,
+        creator_hash,
+        source_proof
+    )?;
+
+    let source_definition = &mut ctx.accounts.source_definition;
+    source_definition.burned = source_definition.burned
+        .checked_add(burned)
+        .ok_or(NftAuctionError::InvalidCount)?;
+
+    // The evolved card is minted to the owner by `claim_evolved_card`
+    let evolution_record = &mut ctx.accounts.evolution_record;
+    evolution_record.owner = ctx.accounts.owner.key();
+    evolution_record.merkle_tree = ctx.accounts.merkle_tree.key();
+    evolution_record.nonce = args.leaf.nonce;
+    evolution_record.source_definition = ctx.accounts.source_definition.key();
+    evolution_record.target_definition = ctx.accounts.target_definition.key();
+    evolution_record.cost = cost;
+    evolution_record.evolved_at = Clock::get()?.unix_timestamp;
+    evolution_record.claimed = false;
+    evolution_record.evolved_tree = Pubkey::default();
+    evolution_record.evolved_nonce = 0;
+    evolution_record.version = EvolutionRecord::VERSION;
+    evolution_record.bump = ctx.bumps.evolution_record;
+
+    emit!(CardEvolved {
+        owner: evolution_record.owner,
+        merkle_tree: evolution_record.merkle_tree,
+        nonce: evolution_record.nonce,
+        source_definition: evolution_record.source_definition,
+        target_definition: evolution_record.target_definition,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{ Mint, TokenAccount, TokenInterface };
+use crate::{
+    constants::{ ROLES_SEED, SOL_PAYMENT_MINT, TREASURY_SEED, WITHDRAWAL_REQUEST_SEED },
+    errors::NftAuctionError,
+    instructions::withdraw_treasury::transfer_from_treasury,
+    state::{ Role, Roles, Treasury, WithdrawalRequest },
+};
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::Treasurer) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [WITHDRAWAL_REQUEST_SEED, &withdrawal_request.id.to_le_bytes()],
+        bump = withdrawal_request.bump,
+        has_one = destination @ NftAuctionError::InvalidWithdrawal
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Must be the destination recorded in the request
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+    let withdrawal_request = &ctx.accounts.withdrawal_request;
+    let mint = ctx.accounts.mint
+        .as_ref()
+        .map(|mint| mint.key())
+        .unwrap_or(SOL_PAYMENT_MINT);
+    require_keys_eq!(mint, withdrawal_request.mint, NftAuctionError::InvalidWithdrawal);
+    require!(
+        Clock::get()?.unix_timestamp >= withdrawal_request.executable_at,
+        NftAuctionError::TimelockNotElapsed
+    );
+
+    // Queued withdrawals went through the timelock and don't count against the daily cap
+    transfer_from_treasury(
+        &ctx.accounts.treasury,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.treasury_token_account.as_deref(),
+        &ctx.accounts.destination.to_account_info(),
+        ctx.accounts.token_program.as_ref(),
+        withdrawal_request.amount
+    )
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::constants::{ CARD_SET_SEED, CRAFT_RANDOMNESS_BINDING_SEED, CRAFT_REQUEST_SEED };
+use crate::errors::NftAuctionError;
+use crate::events::CardCrafted;
+use crate::state::{ CardSet, CraftRequest, RandomnessBinding, RandomnessProvider };
+use crate::randomness::{ binding_seed, reveal_randomness };
+use crate::draw::draw_from_tier;
+
+#[derive(Accounts)]
+#[instruction(force: [u8; 32])]
+pub struct FulfillCraft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        mut,
+        seeds = [CRAFT_REQUEST_SEED, &force],
+        bump = craft_request.bump,
+        has_one = user,
+        has_one = card_set
+    )]
+    pub craft_request: Box<Account<'info, CraftRequest>>,
+
+    /// CHECK: Randomness account recorded by `craft`
+    #[account(address = craft_request.randomness)]
+    pub random: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CRAFT_RANDOMNESS_BINDING_SEED,
+            random.key().as_ref(),
+            binding_seed(random.key, &force),
+        ],
+        bump = randomness_binding.bump
+    )]
+    pub randomness_binding: Box<Account<'info, RandomnessBinding>>,
+}
+
+pub fn fulfill_craft(ctx: Context<FulfillCraft>, _force: [u8; 32]) -> Result<()> {
+    let craft_request = &mut ctx.accounts.craft_request;
+    require!(!craft_request.revealed, NftAuctionError::AlreadyRevealed);
+    ctx.accounts.randomness_binding.consume(craft_request.key())?;
+
+    let seed = reveal_randomness(
+        RandomnessProvider::Orao,
+        &ctx.accounts.random,
+        &craft_request.force,
+        0
+    )?.ok_or(NftAuctionError::StillProcessing)?;
+
+    let tier_size = ctx.accounts.card_set.cards_by_rarity[craft_request.target_rarity as usize];
+    let card = draw_from_tier(&seed, tier_size, craft_request.target_rarity)?;
+    msg!("Crafted card: rarity {} index {}", card.rarity, card.index);
+
+    craft_request.card = Some(card);
+    craft_request.revealed = true;
+
+    emit!(CardCrafted {
+        user: craft_request.user,
+        card_set: craft_request.card_set,
+        rarity: card.rarity,
+        index: card.index,
+    });
+
+    Ok(())
+}
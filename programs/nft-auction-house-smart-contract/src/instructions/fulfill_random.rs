@@ -1,9 +1,23 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{ token::Token, associated_token::AssociatedToken };
-use orao_solana_vrf::RANDOMNESS_ACCOUNT_SEED;
+use crate::constants::{
+    CARD_SET_SEED,
+    PACK_PURCHASE_SEED,
+    PACK_RANDOMNESS_BINDING_SEED,
+    PLAYER_PACK_STATE_SEED,
+};
 use crate::errors::NftAuctionError;
-use crate::state::NftList;
-use crate::misc::*;
+use crate::draw::simulate_draw;
+use crate::state::{
+    CardSet,
+    DropTable,
+    PackConfig,
+    PackPool,
+    PackPurchase,
+    PlayerPackState,
+    RandomnessBinding,
+};
+use crate::randomness::{ binding_seed, reveal_randomness };
 
 #[derive(Accounts)]
 #[instruction(force: [u8; 32])]
@@ -14,18 +28,53 @@ pub struct FulfillRandom<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut)]
-    pub nft_list: Account<'info, NftList>,
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(has_one = card_set)]
+    pub pack_config: Box<Account<'info, PackConfig>>,
+
+    #[account(
+        mut,
+        seeds = [PACK_PURCHASE_SEED, &force],
+        bump = pack_purchase.bump,
+        has_one = user,
+        has_one = card_set,
+        has_one = pack_config
+    )]
+    pub pack_purchase: Box<Account<'info, PackPurchase>>,
+
+    #[account(address = pack_purchase.drop_table)]
+    pub drop_table: Box<Account<'info, DropTable>>,
+
+    #[account(mut, address = pack_purchase.pack_pool)]
+    pub pack_pool: Option<Box<Account<'info, PackPool>>>,
 
-    /// CHECK: Randomness
     #[account(
         mut,
-        seeds = [RANDOMNESS_ACCOUNT_SEED, &force],
-        bump,
-        seeds::program = orao_solana_vrf::ID
+        seeds = [PLAYER_PACK_STATE_SEED, user.key().as_ref(), pack_config.key().as_ref()],
+        bump = player_pack_state.bump
     )]
+    pub player_pack_state: Box<Account<'info, PlayerPackState>>,
+
+    /// CHECK: Randomness account of the current request, read by the purchase's provider
+    #[account(address = pack_purchase.randomness)]
     pub random: AccountInfo<'info>,
 
+    #[account(
+        mut,
+        seeds = [
+            PACK_RANDOMNESS_BINDING_SEED,
+            random.key().as_ref(),
+            binding_seed(random.key, &pack_purchase.request_seed),
+        ],
+        bump = randomness_binding.bump
+    )]
+    pub randomness_binding: Box<Account<'info, RandomnessBinding>>,
+
     pub token_program: Program<'info, Token>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -36,11 +85,51 @@ pub struct FulfillRandom<'info> {
 }
 
 pub fn fulfill_random(ctx: Context<FulfillRandom>, _force: [u8; 32]) -> Result<()> {
-    let rand_acc = crate::misc::get_account_data(&ctx.accounts.random)?;
+    let pack_purchase = &mut ctx.accounts.pack_purchase;
+    require!(!pack_purchase.revealed, NftAuctionError::AlreadyRevealed);
+    ctx.accounts.randomness_binding.consume(pack_purchase.key())?;
+
+    let seed = reveal_randomness(
+        pack_purchase.randomness_provider,
+        &ctx.accounts.random,
+        &pack_purchase.request_seed,
+        pack_purchase.commit_slot
+    )?.ok_or(NftAuctionError::StillProcessing)?;
+    msg!("Random number: {}", u64::from_le_bytes(seed[0..8].try_into().unwrap()));
+    let player_pack_state = &mut ctx.accounts.player_pack_state;
+    require!(
+        pack_purchase.sequence == player_pack_state.revealed_purchases,
+        NftAuctionError::RevealOutOfOrder
+    );
+    player_pack_state.revealed_purchases = player_pack_state.revealed_purchases.saturating_add(1);
+    pack_purchase.pity_before = player_pack_state.packs_since_pity;
+
+    let pool = if pack_purchase.pack_pool != Pubkey::default() {
+        let pack_pool = ctx.accounts.pack_pool.as_mut().ok_or(NftAuctionError::InvalidPackPool)?;
+        let card_count = (pack_purchase.pack_count as u64) * (pack_purchase.cards_per_pack as u64);
+        pack_pool.reserved = pack_pool.reserved.saturating_sub(card_count);
+        Some(&mut ***pack_pool)
+    } else {
+        None
+    };
+
+    // Odds come from the drop table the pack was bought under, not the currently active one
+    let outcome = simulate_draw(
+        &seed,
+        &ctx.accounts.drop_table,
+        pool,
+        pack_purchase.cards_per_pack,
+        pack_purchase.pack_count,
+        player_pack_state.packs_since_pity
+    )?;
+    player_pack_state.packs_since_pity = outcome.pity_counter;
+
+    for card in outcome.cards.iter() {
+        msg!("Drawn card: rarity {} index {}", card.rarity, card.index);
+    }
 
-    let randomness = current_state(&rand_acc);
-    msg!("Orao Random number: {}", randomness);
-    require!(randomness != 0, NftAuctionError::StillProcessing);
+    pack_purchase.cards = outcome.cards;
+    pack_purchase.revealed = true;
 
     Ok(())
 }
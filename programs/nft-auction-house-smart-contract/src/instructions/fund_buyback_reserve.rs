@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ self, Mint, TokenAccount, TokenInterface },
+};
+use crate::{
+    constants::{ BUYBACK_RESERVE_SEED, CONFIG_SEED },
+    errors::CustomError,
+    state::{ BuybackReserve, Config },
+};
+
+#[derive(Accounts)]
+pub struct FundBuybackReserve<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [BUYBACK_RESERVE_SEED], bump = buyback_reserve.bump)]
+    pub buyback_reserve: Box<Account<'info, BuybackReserve>>,
+
+    #[account(address = global_config.usdc_mint @ CustomError::InvalidUsdcMint)]
+    pub usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = funder
+    )]
+    pub funder_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = buyback_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub reserve_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_buyback_reserve(ctx: Context<FundBuybackReserve>, amount: u64) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.funder_usdc_account.to_account_info(),
+                mint: ctx.accounts.usdc_mint.to_account_info(),
+                to: ctx.accounts.reserve_usdc_account.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            }
+        ),
+        amount,
+        ctx.accounts.usdc_mint.decimals
+    )?;
+
+    let reserve_balance = ctx.accounts.reserve_usdc_account.amount + amount;
+    ctx.accounts.buyback_reserve.refresh(reserve_balance);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CONFIG_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ Config, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct InitRoles<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = admin @ NftAuctionError::Unauthorized
+    )]
+    pub global_config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [ROLES_SEED],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// The config admin becomes the first super admin
+pub fn init_roles(ctx: Context<InitRoles>) -> Result<()> {
+    let roles = &mut ctx.accounts.roles;
+    roles.members = Vec::new();
+    roles.grant(ctx.accounts.admin.key(), Role::SuperAdmin)?;
+    roles.version = Roles::VERSION;
+    roles.bump = ctx.bumps.roles;
+
+    Ok(())
+}
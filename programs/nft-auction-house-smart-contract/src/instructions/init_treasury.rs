@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ ROLES_SEED, TREASURY_SEED },
+    errors::NftAuctionError,
+    state::{ Role, Roles, Treasury },
+};
+
+#[derive(Accounts)]
+pub struct InitTreasury<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_treasury(ctx: Context<InitTreasury>, timelock: i64) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.set_timelock(timelock)?;
+    treasury.limits = Vec::new();
+    treasury.next_request_id = 0;
+    treasury.version = Treasury::VERSION;
+    treasury.bump = ctx.bumps.treasury;
+
+    Ok(())
+}
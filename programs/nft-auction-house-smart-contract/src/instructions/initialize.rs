@@ -1,14 +1,26 @@
 use anchor_lang::prelude::*;
-use crate::{ constants::{ CONFIG_SEED, NFT_LIST_SEED }, state::Config };
+use anchor_spl::token_interface::Mint;
+use crate::{
+    constants::{ ALLOW_CUSTOM_PAYMENT_MINT, CONFIG_SEED, USDC_MINT },
+    errors::{ CustomError, NftAuctionError },
+    state::Config,
+};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    #[account(init, payer = admin, space = 8 + 32 + 32 + 2, seeds = [CONFIG_SEED], bump)]
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [CONFIG_SEED], bump)]
     pub global_config: Account<'info, Config>,
 
+    // Must be this cluster's USDC unless built for localnet
+    #[account(
+        constraint = ALLOW_CUSTOM_PAYMENT_MINT ||
+            usdc_mint.key() == USDC_MINT @ CustomError::InvalidUsdcMint
+    )]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -17,11 +29,15 @@ pub fn initialize(
     platform_vault: Pubkey,
     fee_percentage: u16
 ) -> Result<()> {
+    require!(fee_percentage <= 10000, NftAuctionError::InvalidFeePercentage);
+
     let config = &mut ctx.accounts.global_config;
 
     config.admin = ctx.accounts.admin.key();
     config.platform_vault = platform_vault;
     config.fee_percentage = fee_percentage;
+    config.usdc_mint = ctx.accounts.usdc_mint.key();
+    config.version = Config::VERSION;
 
     Ok(())
 }
@@ -1,12 +1,35 @@
-use anchor_lang::{ prelude::*, solana_program::{ instruction::Instruction, program::invoke } };
+use anchor_lang::prelude::*;
 use anchor_spl::{ associated_token::AssociatedToken, token::{ self, Token, TokenAccount } };
 use crate::{
-    constants::{ BUBBLEGUM_PROGRAM_ID, CONFIG_SEED, USDC_MINT },
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        BUYBACK_RESERVE_SEED,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        CONFIG_SEED,
+        LEAF_METADATA_SEED,
+        PACK_CONFIG_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+    },
     errors::{ CustomError, NftAuctionError },
-    state::{ Config, TransferArgs },
+    state::{
+        BuybackReserve,
+        CardDefinition,
+        CardSet,
+        CollectionConfig,
+        Config,
+        LeafArgs,
+        LeafMetadata,
+        PackConfig,
+        TransferArgs,
+    },
+    utils::{ card_leaf_hashes, transfer_leaf, BurnLeafAccounts },
 };
 
 #[derive(Accounts)]
+#[instruction(leaf: LeafArgs)]
 pub struct InstantSell<'info> {
     #[account(
         mut,
@@ -15,24 +38,66 @@ pub struct InstantSell<'info> {
     )]
     pub global_config: Account<'info, Config>,
 
+    /// CHECK: Program PDA listed as the verified creator of every card
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, card_set.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    // Definition of the sold card; the leaf hashes are rebuilt from it
+    #[account(has_one = card_set)]
+    pub card_definition: Box<Account<'info, CardDefinition>>,
+
+    // Only for leaves updated on their own by `update_card_metadata`
+    #[account(
+        seeds = [LEAF_METADATA_SEED, merkle_tree.key().as_ref(), &leaf.nonce.to_le_bytes()],
+        bump = leaf_metadata.bump
+    )]
+    pub leaf_metadata: Option<Account<'info, LeafMetadata>>,
+
+    // The pack the card is sold in, which sets its buyback value
+    #[account(
+        seeds = [PACK_CONFIG_SEED, card_set.key().as_ref(), &[card_definition.pack_id]],
+        bump = pack_config.bump,
+        has_one = card_set
+    )]
+    pub pack_config: Box<Account<'info, PackConfig>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK:
-    #[account(mut)]
+    // Co-signs every sale and receives the sold card
+    #[account(
+        constraint = platform_vault.key() == global_config.platform_vault @
+            NftAuctionError::InvalidPlatformVault
+    )]
     pub platform_vault: Signer<'info>,
 
+    #[account(mut, seeds = [BUYBACK_RESERVE_SEED], bump = buyback_reserve.bump)]
+    pub buyback_reserve: Box<Account<'info, BuybackReserve>>,
+
     /// CHECK: USDC mint
-    #[account(constraint = usdc_mint.key() == USDC_MINT @ CustomError::InvalidUsdcMint)]
+    #[account(constraint = usdc_mint.key() == global_config.usdc_mint @ CustomError::InvalidUsdcMint)]
     pub usdc_mint: AccountInfo<'info>,
 
     #[account(
         mut,
         associated_token::mint = usdc_mint,
-        associated_token::authority = platform_vault,
+        associated_token::authority = buyback_reserve,
         associated_token::token_program = token_program
     )]
-    pub vault_usdc_account: Account<'info, TokenAccount>,
+    pub reserve_usdc_account: Account<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -42,10 +107,17 @@ pub struct InstantSell<'info> {
     )]
     pub user_usdc_account: Account<'info, TokenAccount>,
 
-    /// CHECK:
-    #[account(mut)]
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
     pub tree_authority: UncheckedAccount<'info>,
 
+    /// CHECK:
+    pub leaf_delegate: UncheckedAccount<'info>,
+
     /// CHECK:
     #[account(mut)]
     pub merkle_tree: UncheckedAccount<'info>,
@@ -54,10 +126,12 @@ pub struct InstantSell<'info> {
     #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
     pub bubblegum_program: AccountInfo<'info>,
 
-    /// CHECK:
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
     pub compression_program: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
     pub log_wrapper: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
@@ -69,42 +143,71 @@ pub struct InstantSell<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn instant_sell(
-    ctx: Context<InstantSell>,
-    pack_id: u8,
-    transfer_args: TransferArgs
+pub fn instant_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, InstantSell<'info>>,
+    leaf: LeafArgs
 ) -> Result<()> {
-    let config = &ctx.accounts.global_config;
-    let pack_price = match pack_id {
-        0..=5 => crate::constants::PACK_PRICES[pack_id as usize],
-        _ => {
-            return Err(CustomError::InvalidPackId.into());
-        }
+    // Hashes come from the card definition, so the sold leaf is guaranteed to be this card
+    let (data_hash, creator_hash) = card_leaf_hashes(
+        &ctx.accounts.card_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key(),
+        &leaf,
+        ctx.accounts.leaf_metadata.as_deref()
+    )?;
+    let transfer_args = TransferArgs {
+        root: leaf.root,
+        data_hash,
+        creator_hash,
+        nonce: leaf.nonce,
+        index: leaf.index,
     };
-    let sell_amount =
-        ((pack_price as u64) * ((10000 - config.fee_percentage) as u64) * 1_000_000) / 10000;
+
+    // The card leaves the user before any USDC does; the proof is passed as remaining accounts
+    transfer_leaf(
+        BurnLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.user,
+            leaf_delegate: &ctx.accounts.leaf_delegate,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.platform_vault,
+        &transfer_args,
+        ctx.remaining_accounts
+    )?;
+
+    // Same per-card value `buy_pack` added as a liability, so the sale settles exactly that
+    let nominal_amount = ctx.accounts.global_config.buyback_price(ctx.accounts.pack_config.price);
+
+    // Scale against the reserve's actual balance, not the last recorded one
+    let buyback_reserve = &mut ctx.accounts.buyback_reserve;
+    buyback_reserve.refresh(ctx.accounts.reserve_usdc_account.amount);
+    let sell_amount = buyback_reserve.scaled_buyback(nominal_amount)?;
 
     require!(
-        ctx.accounts.vault_usdc_account.amount >= sell_amount,
+        ctx.accounts.reserve_usdc_account.amount >= sell_amount,
         NftAuctionError::InsufficientFunds
     );
 
+    let reserve_seeds: &[&[u8]] = &[BUYBACK_RESERVE_SEED, &[buyback_reserve.bump]];
     token::transfer(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), token::Transfer {
-            from: ctx.accounts.vault_usdc_account.to_account_info(),
-            to: ctx.accounts.user_usdc_account.to_account_info(),
-            authority: ctx.accounts.platform_vault.to_account_info(),
-        }),
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.reserve_usdc_account.to_account_info(),
+                to: ctx.accounts.user_usdc_account.to_account_info(),
+                authority: buyback_reserve.to_account_info(),
+            },
+            &[reserve_seeds]
+        ),
         sell_amount
     )?;
 
-    let transfer_discriminator: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
-
-    let mut data = transfer_discriminator[..8].to_vec();
-    data.extend(transfer_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
-
-    // transfer cNFT via Bubblegum CPI
-    // .......
+    buyback_reserve.reserve_balance = ctx.accounts.reserve_usdc_account.amount - sell_amount;
+    buyback_reserve.settle_liability(nominal_amount);
 
     Ok(())
 }
@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{ instruction::{ Instruction, AccountMeta }, program::invoke };
-use crate::constants::TENSOR_CNFT_PROGRAM_ID;
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID,
+    SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID,
+    TENSOR_CNFT_PROGRAM_ID,
+};
 use crate::errors::NftAuctionError;
 
 #[derive(Accounts)]
@@ -19,21 +24,29 @@ pub struct ListCompressedNft<'info> {
     #[account(mut)]
     pub merkle_tree: UncheckedAccount<'info>,
 
-    /// CHECK: Tree authority for the merkle tree
-    #[account(mut)]
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        mut,
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
     pub tree_authority: UncheckedAccount<'info>,
 
     /// CHECK: Listing state account (PDA owned by Tensor)
     #[account(mut)]
     pub list_state: UncheckedAccount<'info>,
 
-    /// CHECK: Bubblegum program for compressed NFT operations
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
     pub bubblegum_program: UncheckedAccount<'info>,
 
-    /// CHECK: Compression program
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
     pub compression_program: UncheckedAccount<'info>,
 
-    /// CHECK: Log wrapper program
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
     pub log_wrapper: UncheckedAccount<'info>,
 
     /// CHECK: Rent payer
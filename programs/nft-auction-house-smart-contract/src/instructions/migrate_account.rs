@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::{
+    constants::ROLES_SEED,
+    errors::NftAuctionError,
+    state::{
+        BuybackReserve,
+        CardDefinition,
+        CardSet,
+        CollectionConfig,
+        CraftRequest,
+        DropTable,
+        EvolutionRecord,
+        EvolutionStake,
+        FeeSchedule,
+        NftList,
+        PackConfig,
+        PackPool,
+        PackPurchase,
+        PlayerPackState,
+        PlayerProfile,
+        RandomnessBinding,
+        Referrer,
+        Role,
+        Roles,
+        Treasury,
+        WithdrawalRequest,
+    },
+};
+
+// Every account type with a leading version byte. `Config` is migrated by `migrate_config`
+// instead, since its version is appended after the original layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionedAccount {
+    CollectionConfig,
+    CardSet,
+    CardDefinition,
+    PackConfig,
+    PackPool,
+    DropTable,
+    PackPurchase,
+    CraftRequest,
+    EvolutionStake,
+    EvolutionRecord,
+    PlayerProfile,
+    PlayerPackState,
+    Treasury,
+    Roles,
+    FeeSchedule,
+    Referrer,
+    BuybackReserve,
+    WithdrawalRequest,
+    RandomnessBinding,
+    NftList,
+}
+
+// Rewrites an account body (version byte onwards) stored at one version into the next version's
+// layout, returning the new body with its version byte updated
+type Converter = fn(&[u8]) -> Result<Vec<u8>>;
+
+// Converters by the version they upgrade from. Every type is still at the version it was
+// created with, so none exist yet; a layout change adds `(old_version, convert_fn)` here.
+const NO_CONVERTERS: &[(u8, Converter)] = &[];
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// CHECK: Read by hand since older layouts may no longer deserialize
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Brings an account created under an older layout up to its type's current version
+pub fn migrate_account(ctx: Context<MigrateAccount>, kind: VersionedAccount) -> Result<()> {
+    match kind {
+        VersionedAccount::CollectionConfig => {
+            migrate::<CollectionConfig>(&ctx, CollectionConfig::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::CardSet => migrate::<CardSet>(&ctx, CardSet::VERSION, NO_CONVERTERS),
+        VersionedAccount::CardDefinition => {
+            migrate::<CardDefinition>(&ctx, CardDefinition::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::PackConfig => {
+            migrate::<PackConfig>(&ctx, PackConfig::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::PackPool => migrate::<PackPool>(&ctx, PackPool::VERSION, NO_CONVERTERS),
+        VersionedAccount::DropTable => {
+            migrate::<DropTable>(&ctx, DropTable::LAYOUT_VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::PackPurchase => {
+            migrate::<PackPurchase>(&ctx, PackPurchase::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::CraftRequest => {
+            migrate::<CraftRequest>(&ctx, CraftRequest::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::EvolutionStake => {
+            migrate::<EvolutionStake>(&ctx, EvolutionStake::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::EvolutionRecord => {
+            migrate::<EvolutionRecord>(&ctx, EvolutionRecord::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::PlayerProfile => {
+            migrate::<PlayerProfile>(&ctx, PlayerProfile::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::PlayerPackState => {
+            migrate::<PlayerPackState>(&ctx, PlayerPackState::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::Treasury => migrate::<Treasury>(&ctx, Treasury::VERSION, NO_CONVERTERS),
+        VersionedAccount::Roles => migrate::<Roles>(&ctx, Roles::VERSION, NO_CONVERTERS),
+        VersionedAccount::FeeSchedule => {
+            migrate::<FeeSchedule>(&ctx, FeeSchedule::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::Referrer => migrate::<Referrer>(&ctx, Referrer::VERSION, NO_CONVERTERS),
+        VersionedAccount::BuybackReserve => {
+            migrate::<BuybackReserve>(&ctx, BuybackReserve::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::WithdrawalRequest => {
+            migrate::<WithdrawalRequest>(&ctx, WithdrawalRequest::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::RandomnessBinding => {
+            migrate::<RandomnessBinding>(&ctx, RandomnessBinding::VERSION, NO_CONVERTERS)
+        }
+        VersionedAccount::NftList => migrate::<NftList>(&ctx, NftList::VERSION, NO_CONVERTERS),
+    }
+}
+
+// Runs the stored body through the converter for each version it passes; a version that is
+// newer than the current one or has no converter is rejected rather than guessed at
+fn migrate<T: Discriminator + Space>(
+    ctx: &Context<MigrateAccount>,
+    current_version: u8,
+    converters: &[(u8, Converter)]
+) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+
+    let body = {
+        let data = account_info.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == *T::DISCRIMINATOR,
+            NftAuctionError::InvalidAccountLayout
+        );
+        require!(data[8] != current_version, NftAuctionError::AccountUpToDate);
+
+        let mut body = data[8..].to_vec();
+        while body[0] != current_version {
+            let from_version = body[0];
+            let convert = converters
+                .iter()
+                .find(|(from, _)| *from == from_version)
+                .map(|(_, convert)| *convert)
+                .ok_or(NftAuctionError::UnknownAccountVersion)?;
+            body = convert(&body)?;
+            require!(
+                !body.is_empty() && body[0] > from_version && body[0] <= current_version,
+                NftAuctionError::UnknownAccountVersion
+            );
+        }
+        body
+    };
+
+    // Grows the account when the current layout is larger; new bytes are zeroed
+    let new_space = (8 + T::INIT_SPACE).max(8 + body.len());
+    if account_info.data_len() < new_space {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_space)
+            .saturating_sub(account_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: account_info.clone(),
+                    }
+                ),
+                rent_due
+            )?;
+        }
+        account_info.resize(new_space)?;
+    }
+
+    let mut data = account_info.try_borrow_mut_data()?;
+    data[8..8 + body.len()].copy_from_slice(&body);
+    data[8 + body.len()..].fill(0);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::{
+    constants::{ CONFIG_SEED, USDC_MINT },
+    errors::NftAuctionError,
+    state::Config,
+};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Read by hand since older layouts no longer deserialize as `Config`
+    #[account(mut, seeds = [CONFIG_SEED], bump, owner = crate::ID)]
+    pub global_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Reallocs an older `Config` to the current layout, filling new fields with defaults
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.global_config.to_account_info();
+
+    let config = {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.len() >= Config::LEGACY_SPACE && data[..8] == *Config::DISCRIMINATOR,
+            NftAuctionError::InvalidConfigLayout
+        );
+
+        let mut body = &data[8..];
+        let admin = Pubkey::deserialize(&mut body)?;
+        let platform_vault = Pubkey::deserialize(&mut body)?;
+        let fee_percentage = u16::deserialize(&mut body)?;
+        require!(fee_percentage <= 10000, NftAuctionError::InvalidFeePercentage);
+
+        let (usdc_mint, version) = match data.len() {
+            Config::LEGACY_SPACE => (USDC_MINT, 0),
+            Config::LEGACY_USDC_SPACE => (Pubkey::deserialize(&mut body)?, 0),
+            _ => (Pubkey::deserialize(&mut body)?, u8::deserialize(&mut body)?),
+        };
+        require!(version < Config::VERSION, NftAuctionError::ConfigUpToDate);
+
+        Config {
+            admin,
+            platform_vault,
+            fee_percentage,
+            usdc_mint,
+            version: Config::VERSION,
+            reserved: [0u8; 64],
+        }
+    };
+    require_keys_eq!(config.admin, ctx.accounts.admin.key(), NftAuctionError::Unauthorized);
+
+    let new_space = 8 + Config::INIT_SPACE;
+    let rent_due = Rent::get()?.minimum_balance(new_space).saturating_sub(config_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: config_info.clone(),
+                }
+            ),
+            rent_due
+        )?;
+    }
+    config_info.resize(new_space)?;
+
+    let mut data = config_info.try_borrow_mut_data()?;
+    config.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
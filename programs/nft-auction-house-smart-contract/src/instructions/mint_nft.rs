@@ -1,7 +1,18 @@
-use crate::constants::BUBBLEGUM_PROGRAM_ID;
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID,
+    BUBBLEGUM_SIGNER_SEED,
+    CARD_SET_SEED,
+    COLLECTION_AUTHORITY_SEED,
+    COLLECTION_CONFIG_SEED,
+    CONFIG_SEED,
+    NFT_LIST_SEED,
+    ROLES_SEED,
+    SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID,
+    TOKEN_METADATA_PROGRAM_ID,
+};
 use crate::errors::NftAuctionError;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{ instruction::Instruction, program::invoke };
 use anchor_spl::{
     token::{ Mint, Token, TokenAccount, MintTo, mint_to },
     associated_token::AssociatedToken,
@@ -17,7 +28,24 @@ use mpl_token_metadata::{
     },
     types::{ CollectionDetails, Creator as MetadataCreator, DataV2 },
 };
-use crate::utils::extract_asset_data_from_mint;
+use crate::state::{
+    CardDefinition,
+    CardSet,
+    CollectionConfig,
+    Config,
+    NftList,
+    Role,
+    Roles,
+    RoyaltyCreator,
+};
+use crate::utils::{
+    card_metadata_args,
+    extract_asset_data_from_mint,
+    mint_card_leaf,
+    validate_metadata,
+    validate_royalties,
+    MintLeafAccounts,
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Creator {
@@ -86,8 +114,23 @@ pub struct CreateCollection<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(mut)]
-    pub collection_authority: Signer<'info>,
+    // Config admin or a holder of the Minter role
+    pub minter: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = minter.key() == global_config.admin ||
+        roles.has_role(minter.key, Role::Minter) @ NftAuctionError::NotMinter
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    /// CHECK: Program PDA that owns the collection and signs mints into it
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
 
     #[account(
         init,
@@ -98,6 +141,33 @@ pub struct CreateCollection<'info> {
     )]
     pub collection_mint: Account<'info, Mint>,
 
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CollectionConfig::INIT_SPACE,
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CardSet::INIT_SPACE,
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + NftList::INIT_SPACE,
+        seeds = [NFT_LIST_SEED, card_set.key().as_ref()],
+        bump
+    )]
+    pub nft_list: Box<Account<'info, NftList>>,
+
     #[account(
         init,
         payer = payer,
@@ -114,7 +184,8 @@ pub struct CreateCollection<'info> {
     #[account(mut)]
     pub collection_master_edition: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: Token Metadata program
+    #[account(constraint = token_metadata_program.key() == TOKEN_METADATA_PROGRAM_ID)]
     pub token_metadata_program: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
@@ -125,8 +196,13 @@ pub struct CreateCollection<'info> {
 
 #[derive(Accounts)]
 pub struct MintCnft<'info> {
-    /// CHECK:
-    #[account(mut)]
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        mut,
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
     pub tree_authority: UncheckedAccount<'info>,
 
     /// CHECK:
@@ -142,17 +218,48 @@ pub struct MintCnft<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // Config admin or a holder of the Minter role
+    pub minter: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = minter.key() == global_config.admin ||
+        roles.has_role(minter.key, Role::Minter) @ NftAuctionError::NotMinter
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
     #[account(mut)]
     pub tree_delegate: Signer<'info>,
 
-    #[account(mut)]
-    pub collection_authority: Signer<'info>,
+    /// CHECK: Program PDA that owns the collection and signs mints into it
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
 
     /// CHECK:
     pub collection_authority_record: UncheckedAccount<'info>,
 
     pub collection_mint: Account<'info, Mint>,
 
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(mut, has_one = card_set)]
+    pub card_definition: Account<'info, CardDefinition>,
+
     /// CHECK: Collection metadata
     #[account(mut)]
     pub collection_metadata: UncheckedAccount<'info>,
@@ -161,43 +268,95 @@ pub struct MintCnft<'info> {
     #[account(mut)]
     pub collection_master_edition: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: Bubblegum collection CPI signer
+    #[account(
+        seeds = [BUBBLEGUM_SIGNER_SEED],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
     pub bubblegum_signer: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
     pub log_wrapper: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
     pub compression_program: UncheckedAccount<'info>,
 
-    /// CHECK:
+    /// CHECK: Token Metadata program
+    #[account(constraint = token_metadata_program.key() == TOKEN_METADATA_PROGRAM_ID)]
     pub token_metadata_program: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
 
-    /// CHECK:
+    /// CHECK: Bubblegum program
     #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
     pub bubblegum_program: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [b"nft_list"],
-        bump
+        seeds = [NFT_LIST_SEED, card_set.key().as_ref()],
+        bump = nft_list.bump
     )]
-    pub nft_list: Account<'info, crate::state::NftList>,
+    pub nft_list: Account<'info, NftList>,
 }
 
 pub fn create_collection<'info>(
     ctx: Context<CreateCollection>,
     name: String,
     symbol: String,
-    uri: String
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Vec<RoyaltyCreator>
 ) -> Result<()> {
-    let creator = vec![MetadataCreator {
+    validate_metadata(&name, &symbol, &uri)?;
+    validate_royalties(
+        seller_fee_basis_points,
+        &creators,
+        ctx.accounts.collection_authority.key()
+    )?;
+
+    let card_set = &mut ctx.accounts.card_set;
+    card_set.collection_mint = ctx.accounts.collection_mint.key();
+    card_set.name = name.clone();
+    card_set.merkle_trees = Vec::new();
+    card_set.cards_by_rarity = Default::default();
+    card_set.pack_config_count = 0;
+    card_set.burn_rewards = Default::default();
+    card_set.craft_input_count = 0;
+    card_set.version = CardSet::VERSION;
+    card_set.bump = ctx.bumps.card_set;
+
+    let nft_list = &mut ctx.accounts.nft_list;
+    nft_list.card_set = ctx.accounts.card_set.key();
+    nft_list.entries = Vec::new();
+    nft_list.version = NftList::VERSION;
+    nft_list.bump = ctx.bumps.nft_list;
+
+    let collection_config = &mut ctx.accounts.collection_config;
+    collection_config.collection_mint = ctx.accounts.collection_mint.key();
+    collection_config.seller_fee_basis_points = seller_fee_basis_points;
+    collection_config.creators = creators;
+    collection_config.version = CollectionConfig::VERSION;
+    collection_config.bump = ctx.bumps.collection_config;
+
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[COLLECTION_AUTHORITY_SEED, &[ctx.bumps.collection_authority]],
+    ];
+
+    let mut creator = vec![MetadataCreator {
         address: ctx.accounts.collection_authority.key(),
         verified: true,
-        share: 100,
+        share: 0,
     }];
+    creator.extend(
+        ctx.accounts.collection_config.creators.iter().map(|c| MetadataCreator {
+            address: c.address,
+            verified: false,
+            share: c.share,
+        })
+    );
 
     CreateMetadataAccountV3Cpi::new(
         &ctx.accounts.token_metadata_program,
@@ -215,7 +374,7 @@ pub fn create_collection<'info>(
                 name,
                 symbol,
                 uri,
-                seller_fee_basis_points: 0,
+                seller_fee_basis_points,
                 creators: Some(creator),
                 collection: None,
                 uses: None,
@@ -223,13 +382,17 @@ pub fn create_collection<'info>(
             is_mutable: true,
             collection_details: Some(CollectionDetails::V1 { size: 0 }),
         }
-    ).invoke()?;
-
-    let mint_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), MintTo {
-        mint: ctx.accounts.collection_mint.to_account_info(),
-        to: ctx.accounts.collection_token_account.to_account_info(),
-        authority: ctx.accounts.collection_authority.to_account_info(),
-    });
+    ).invoke_signed(signer_seeds)?;
+
+    let mint_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            to: ctx.accounts.collection_token_account.to_account_info(),
+            authority: ctx.accounts.collection_authority.to_account_info(),
+        },
+        signer_seeds
+    );
     mint_to(mint_ctx, 1)?;
 
     CreateMasterEditionV3Cpi::new(
@@ -248,100 +411,54 @@ pub fn create_collection<'info>(
         CreateMasterEditionV3InstructionArgs {
             max_supply: Some(0),
         }
-    ).invoke()?;
+    ).invoke_signed(signer_seeds)?;
     Ok(())
 }
 
-pub fn cpi_mint_cnft<'info>(
-    ctx: Context<MintCnft>,
-    name: String,
-    symbol: String,
-    uri: String
-) -> Result<()> {
-    let discriminator = anchor_lang::solana_program::hash
-        ::hash(b"global:mint_to_collection_v1")
-        .to_bytes();
-
-    let mut data = discriminator[..8].to_vec();
-
-    let metadata_args = MintToCollectionV1Args {
-        metadata_args: MetadataArgs {
-            name: name,
-            symbol: symbol,
-            uri: uri,
-            seller_fee_basis_points: 500,
-            primary_sale_happened: false,
-            is_mutable: true,
-            edition_nonce: None,
-            token_standard: Some(TokenStandard::NonFungible),
-            collection: Some(Collection {
-                verified: false,
-                key: ctx.accounts.collection_mint.key(),
-            }),
-            uses: None,
-            token_program_version: TokenProgramVersion::Original,
-            creators: vec![Creator {
-                address: ctx.accounts.collection_authority.key(),
-                verified: false,
-                share: 100,
-            }],
-        },
-    };
-
-    data.extend(metadata_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
-
-    let accounts = vec![
-        AccountMeta::new(ctx.accounts.tree_authority.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.leaf_owner.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.leaf_delegate.key(), false),
-        AccountMeta::new(ctx.accounts.merkle_tree.key(), false),
-        AccountMeta::new(ctx.accounts.payer.key(), true),
-        AccountMeta::new_readonly(ctx.accounts.tree_delegate.key(), true),
-        AccountMeta::new_readonly(ctx.accounts.collection_authority.key(), true),
-        AccountMeta::new_readonly(ctx.accounts.bubblegum_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.collection_mint.key(), false),
-        AccountMeta::new(ctx.accounts.collection_metadata.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.collection_master_edition.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.bubblegum_signer.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.log_wrapper.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.compression_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.token_metadata_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false)
+pub fn cpi_mint_cnft<'info>(ctx: Context<MintCnft>) -> Result<()> {
+    let metadata_args = card_metadata_args(
+        &ctx.accounts.card_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key()
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[COLLECTION_AUTHORITY_SEED, &[ctx.bumps.collection_authority]],
     ];
 
-    let ix = Instruction {
-        program_id: BUBBLEGUM_PROGRAM_ID,
-        accounts,
-        data,
-    };
-
-    invoke(
-        &ix,
-        &[
-            ctx.accounts.tree_authority.to_account_info(),
-            ctx.accounts.leaf_owner.to_account_info(),
-            ctx.accounts.leaf_delegate.to_account_info(),
-            ctx.accounts.merkle_tree.to_account_info(),
-            ctx.accounts.payer.to_account_info(),
-            ctx.accounts.tree_delegate.to_account_info(),
-            ctx.accounts.collection_authority.to_account_info(),
-            ctx.accounts.bubblegum_program.to_account_info(),
-            ctx.accounts.collection_mint.to_account_info(),
-            ctx.accounts.collection_metadata.to_account_info(),
-            ctx.accounts.collection_master_edition.to_account_info(),
-            ctx.accounts.bubblegum_signer.to_account_info(),
-            ctx.accounts.log_wrapper.to_account_info(),
-            ctx.accounts.compression_program.to_account_info(),
-            ctx.accounts.token_metadata_program.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ]
+    mint_card_leaf(
+        MintLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.leaf_owner,
+            leaf_delegate: &ctx.accounts.leaf_delegate,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            payer: &ctx.accounts.payer,
+            tree_delegate: &ctx.accounts.tree_delegate,
+            collection_authority: &ctx.accounts.collection_authority,
+            bubblegum_program: &ctx.accounts.bubblegum_program,
+            collection_mint: &ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: &ctx.accounts.collection_metadata,
+            collection_master_edition: &ctx.accounts.collection_master_edition,
+            bubblegum_signer: &ctx.accounts.bubblegum_signer,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            token_metadata_program: &ctx.accounts.token_metadata_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &metadata_args,
+        signer_seeds
     )?;
 
     let asset_data = extract_asset_data_from_mint(
         &ctx.accounts.merkle_tree.key(),
         &ctx.accounts.leaf_owner.key(),
-        &metadata_args.metadata_args
+        &metadata_args
     )?;
 
+    let card_definition = &mut ctx.accounts.card_definition;
+    card_definition.minted = card_definition.minted
+        .checked_add(1)
+        .ok_or(NftAuctionError::InvalidCount)?;
+
     Ok(())
 }
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CONFIG_SEED, MOCK_RANDOMNESS_SEED },
+    errors::NftAuctionError,
+    state::{ Config, MockRandomness },
+};
+
+#[derive(Accounts)]
+#[instruction(force: [u8; 32])]
+pub struct MockFulfill<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = admin @ NftAuctionError::Unauthorized
+    )]
+    pub global_config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [MOCK_RANDOMNESS_SEED, &force],
+        bump = mock_randomness.bump
+    )]
+    pub mock_randomness: Account<'info, MockRandomness>,
+}
+
+pub fn mock_fulfill(ctx: Context<MockFulfill>, _force: [u8; 32], randomness: [u8; 64]) -> Result<()> {
+    ctx.accounts.mock_randomness.value = Some(randomness);
+    Ok(())
+}
@@ -1,17 +1,93 @@
 pub mod initialize;
+pub mod migrate_config;
+pub mod migrate_account;
+pub mod init_roles;
+pub mod update_roles;
+pub mod set_fee_schedule;
+pub mod register_referrer;
+pub mod claim_referral;
+pub mod init_treasury;
+pub mod update_treasury;
+pub mod withdraw_treasury;
+pub mod queue_withdrawal;
+pub mod execute_withdrawal;
+pub mod cancel_withdrawal;
+pub mod configure_buyback_reserve;
+pub mod fund_buyback_reserve;
 pub mod buy_pack;
 pub mod fulfill_random;
+pub mod rerequest_randomness;
 pub mod transfer;
 pub mod instant_sell;
 pub mod mint_nft;
 pub mod list;
 pub mod delist;
+pub mod add_merkle_tree;
+pub mod add_card_definition;
+pub mod set_pack_config;
+pub mod update_pack_config;
+pub mod create_drop_table;
+pub mod update_drop_table;
+pub mod activate_drop_table;
+pub mod create_pack_pool;
+pub mod add_pool_cards;
+pub mod update_card_metadata;
+pub mod set_burn_rewards;
+pub mod set_craft_input_count;
+pub mod burn_card;
+pub mod craft;
+pub mod fulfill_craft;
+pub mod claim_crafted_card;
+pub mod set_evolution;
+pub mod start_evolution;
+pub mod evolve;
+pub mod claim_evolved_card;
+#[cfg(feature = "mock-vrf")]
+pub mod mock_fulfill;
 
 pub use initialize::*;
+pub use migrate_config::*;
+pub use migrate_account::*;
+pub use init_roles::*;
+pub use update_roles::*;
+pub use set_fee_schedule::*;
+pub use register_referrer::*;
+pub use claim_referral::*;
+pub use init_treasury::*;
+pub use update_treasury::*;
+pub use withdraw_treasury::*;
+pub use queue_withdrawal::*;
+pub use execute_withdrawal::*;
+pub use cancel_withdrawal::*;
+pub use configure_buyback_reserve::*;
+pub use fund_buyback_reserve::*;
 pub use buy_pack::*;
 pub use fulfill_random::*;
+pub use rerequest_randomness::*;
 pub use transfer::*;
 pub use instant_sell::*;
 pub use mint_nft::*;
 pub use list::*;
 pub use delist::*;
+pub use add_merkle_tree::*;
+pub use add_card_definition::*;
+pub use set_pack_config::*;
+pub use update_pack_config::*;
+pub use create_drop_table::*;
+pub use update_drop_table::*;
+pub use activate_drop_table::*;
+pub use create_pack_pool::*;
+pub use add_pool_cards::*;
+pub use update_card_metadata::*;
+pub use set_burn_rewards::*;
+pub use set_craft_input_count::*;
+pub use burn_card::*;
+pub use craft::*;
+pub use fulfill_craft::*;
+pub use claim_crafted_card::*;
+pub use set_evolution::*;
+pub use start_evolution::*;
+pub use evolve::*;
+pub use claim_evolved_card::*;
+#[cfg(feature = "mock-vrf")]
+pub use mock_fulfill::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ ROLES_SEED, TREASURY_SEED, WITHDRAWAL_REQUEST_SEED },
+    errors::NftAuctionError,
+    state::{ Role, Roles, Treasury, WithdrawalRequest },
+};
+
+#[derive(Accounts)]
+pub struct QueueWithdrawal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::Treasurer) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + WithdrawalRequest::INIT_SPACE,
+        seeds = [WITHDRAWAL_REQUEST_SEED, &treasury.next_request_id.to_le_bytes()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// `mint` is `SOL_PAYMENT_MINT` for lamports; `destination` is then a wallet,
+// otherwise a token account of `mint`
+pub fn queue_withdrawal(
+    ctx: Context<QueueWithdrawal>,
+    mint: Pubkey,
+    amount: u64,
+    destination: Pubkey
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    let withdrawal_request = &mut ctx.accounts.withdrawal_request;
+
+    withdrawal_request.id = treasury.next_request_id;
+    withdrawal_request.mint = mint;
+    withdrawal_request.amount = amount;
+    withdrawal_request.destination = destination;
+    withdrawal_request.executable_at = Clock::get()?.unix_timestamp.saturating_add(treasury.timelock);
+    withdrawal_request.version = WithdrawalRequest::VERSION;
+    withdrawal_request.bump = ctx.bumps.withdrawal_request;
+
+    treasury.next_request_id += 1;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{ constants::REFERRER_SEED, state::Referrer };
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [REFERRER_SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.authority = ctx.accounts.authority.key();
+    referrer.balances = Vec::new();
+    referrer.referred_purchases = 0;
+    referrer.version = Referrer::VERSION;
+    referrer.bump = ctx.bumps.referrer;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use orao_solana_vrf::program::OraoVrf;
+use crate::{
+    constants::{
+        PACK_PURCHASE_SEED,
+        PACK_RANDOMNESS_BINDING_SEED,
+        RANDOMNESS_RETRY_SLOTS,
+        ROLES_SEED,
+    },
+    errors::NftAuctionError,
+    randomness::{ binding_seed, request_randomness, reveal_randomness, RandomnessRequest },
+    state::{ PackPurchase, RandomnessBinding, Role, Roles },
+};
+
+#[derive(Accounts)]
+#[instruction(force: [u8; 32], request_seed: [u8; 32])]
+pub struct RerequestRandomness<'info> {
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(guardian.key, Role::Guardian) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [PACK_PURCHASE_SEED, &force],
+        bump = pack_purchase.bump,
+        constraint = !pack_purchase.revealed @ NftAuctionError::AlreadyRevealed
+    )]
+    pub pack_purchase: Box<Account<'info, PackPurchase>>,
+
+    /// CHECK: Randomness account of the request being replaced
+    #[account(address = pack_purchase.randomness)]
+    pub previous_random: AccountInfo<'info>,
+
+    /// CHECK: New randomness account of the purchase's provider, validated by the provider.
+    /// Passed writable for ORAO and the mock only; the SlotHashes sysvar can't be writable.
+    pub random: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = guardian,
+        space = 8 + RandomnessBinding::INIT_SPACE,
+        seeds = [
+            PACK_RANDOMNESS_BINDING_SEED,
+            random.key().as_ref(),
+            binding_seed(random.key, &request_seed),
+        ],
+        bump
+    )]
+    pub randomness_binding: Box<Account<'info, RandomnessBinding>>,
+
+    /// CHECK: ORAO treasury, only needed for ORAO packs; checked against the network state
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: ORAO network state, validated by the provider
+    #[account(mut)]
+    pub config: Option<UncheckedAccount<'info>>,
+
+    pub vrf: Option<Program<'info, OraoVrf>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Gives a purchase whose randomness was never revealed (a missed Switchboard reveal slot, an
+// expired slot hash) a new request, so it can still be revealed and release its pool reservation.
+// Guardian-only: a buyer able to re-request could withhold reveals they dislike.
+pub fn rerequest_randomness(
+    ctx: Context<RerequestRandomness>,
+    _force: [u8; 32],
+    request_seed: [u8; 32]
+) -> Result<()> {
+    let pack_purchase = &ctx.accounts.pack_purchase;
+    let current_slot = Clock::get()?.slot;
+    require!(
+        current_slot > pack_purchase.commit_slot.saturating_add(RANDOMNESS_RETRY_SLOTS),
+        NftAuctionError::RandomnessNotStale
+    );
+
+    let previous = reveal_randomness(
+        pack_purchase.randomness_provider,
+        &ctx.accounts.previous_random,
+        &pack_purchase.request_seed,
+        pack_purchase.commit_slot
+    );
+    require!(!matches!(previous, Ok(Some(_))), NftAuctionError::RandomnessNotStale);
+
+    let randomness_provider = pack_purchase.randomness_provider;
+    let commit_slot = request_randomness(
+        randomness_provider,
+        &(RandomnessRequest {
+            payer: &ctx.accounts.guardian.to_account_info(),
+            random: &ctx.accounts.random,
+            network_state: ctx.accounts.config.as_ref().map(|config| config.to_account_info()),
+            treasury: ctx.accounts.treasury.as_ref().map(|treasury| treasury.to_account_info()),
+            vrf_program: ctx.accounts.vrf.as_ref().map(|vrf| vrf.to_account_info()),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        }),
+        request_seed
+    )?;
+
+    let pack_purchase = &mut ctx.accounts.pack_purchase;
+    pack_purchase.randomness = ctx.accounts.random.key();
+    pack_purchase.request_seed = request_seed;
+    pack_purchase.commit_slot = commit_slot;
+
+    ctx.accounts.randomness_binding.bind(
+        ctx.accounts.random.key(),
+        ctx.accounts.pack_purchase.key(),
+        ctx.bumps.randomness_binding
+    )?;
+
+    Ok(())
+}
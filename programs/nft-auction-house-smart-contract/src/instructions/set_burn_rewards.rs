@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, RARITY_TIERS, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct SetBurnRewards<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+}
+
+pub fn set_burn_rewards(ctx: Context<SetBurnRewards>, rewards: [u64; RARITY_TIERS]) -> Result<()> {
+    ctx.accounts.card_set.burn_rewards = rewards;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct SetCraftInputCount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+}
+
+pub fn set_craft_input_count(
+    ctx: Context<SetCraftInputCount>,
+    craft_input_count: u8
+) -> Result<()> {
+    ctx.accounts.card_set.craft_input_count = craft_input_count;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::ROLES_SEED,
+    errors::NftAuctionError,
+    state::{ CardDefinition, EvolutionCost, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct SetEvolution<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(mut)]
+    pub card_definition: Account<'info, CardDefinition>,
+
+    #[account(
+        constraint = target_definition.card_set == card_definition.card_set @ NftAuctionError::InvalidEvolutionTarget,
+        constraint = target_definition.key() != card_definition.key() @ NftAuctionError::InvalidEvolutionTarget
+    )]
+    pub target_definition: Option<Account<'info, CardDefinition>>,
+}
+
+// Passing no target clears the evolution link
+pub fn set_evolution(ctx: Context<SetEvolution>, cost: EvolutionCost) -> Result<()> {
+    if let EvolutionCost::Staking { duration } = cost {
+        require!(duration > 0, NftAuctionError::InvalidCount);
+    }
+
+    let evolves_into = ctx.accounts.target_definition.as_ref().map(|target| target.key());
+
+    let card_definition = &mut ctx.accounts.card_definition;
+    card_definition.evolves_into = evolves_into;
+    card_definition.evolution_cost = cost;
+
+    Ok(())
+}
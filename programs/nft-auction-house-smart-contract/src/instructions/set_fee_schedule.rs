@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ FEE_SCHEDULE_SEED, REFERRAL_POOL_SEED, ROLES_SEED, TREASURY_SEED },
+    errors::NftAuctionError,
+    fees::fund_rent_exempt,
+    state::{ FeeSchedule, Role, Roles, Treasury },
+};
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + FeeSchedule::INIT_SPACE,
+        seeds = [FEE_SCHEDULE_SEED],
+        bump
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+
+    // The treasury share always goes to the program-owned treasury and its withdrawal limits
+    #[account(seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Program PDA holding the referral share until it is claimed
+    #[account(mut, seeds = [REFERRAL_POOL_SEED], bump)]
+    pub referral_pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// `shares_bps` is treasury, creator, partner, referral pool
+pub fn set_fee_schedule(
+    ctx: Context<SetFeeSchedule>,
+    creator: Pubkey,
+    partner: Pubkey,
+    shares_bps: [u16; 4]
+) -> Result<()> {
+    let total: u32 = shares_bps
+        .iter()
+        .map(|bps| *bps as u32)
+        .sum();
+    require!(total == 10000, NftAuctionError::InvalidFeeSplit);
+
+    let fee_schedule = &mut ctx.accounts.fee_schedule;
+    fee_schedule.treasury = ctx.accounts.treasury.key();
+    fee_schedule.creator = creator;
+    fee_schedule.partner = partner;
+    fee_schedule.treasury_bps = shares_bps[0];
+    fee_schedule.creator_bps = shares_bps[1];
+    fee_schedule.partner_bps = shares_bps[2];
+    fee_schedule.referral_bps = shares_bps[3];
+    fee_schedule.version = FeeSchedule::VERSION;
+    fee_schedule.bump = ctx.bumps.fee_schedule;
+
+    // SOL referral shares of any size must land in the pool, so it is made rent exempt up front
+    fund_rent_exempt(
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.referral_pool.to_account_info(),
+        &ctx.accounts.system_program.to_account_info()
+    )?;
+
+    Ok(())
+}
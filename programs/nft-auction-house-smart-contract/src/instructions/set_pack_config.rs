@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, MAX_CARDS_PER_PURCHASE, PACK_CONFIG_SEED, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, PackConfig, Role, Roles },
+};
+
+#[derive(Accounts)]
+#[instruction(pack_id: u8)]
+pub struct SetPackConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PackConfig::INIT_SPACE,
+        seeds = [PACK_CONFIG_SEED, card_set.key().as_ref(), &[pack_id]],
+        bump
+    )]
+    pub pack_config: Account<'info, PackConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_pack_config(
+    ctx: Context<SetPackConfig>,
+    pack_id: u8,
+    price: u64,
+    cards_per_pack: u8
+) -> Result<()> {
+    require!(
+        cards_per_pack > 0 && (cards_per_pack as usize) <= MAX_CARDS_PER_PURCHASE,
+        NftAuctionError::InvalidCount
+    );
+
+    let card_set = &mut ctx.accounts.card_set;
+    let pack_config = &mut ctx.accounts.pack_config;
+    if pack_config.card_set == Pubkey::default() {
+        card_set.pack_config_count = card_set.pack_config_count.saturating_add(1);
+    }
+
+    pack_config.card_set = card_set.key();
+    pack_config.pack_id = pack_id;
+    pack_config.price = price;
+    pack_config.cards_per_pack = cards_per_pack;
+    pack_config.version = PackConfig::VERSION;
+    pack_config.bump = ctx.bumps.pack_config;
+
+    Ok(())
+}
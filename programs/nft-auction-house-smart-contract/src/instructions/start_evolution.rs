@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        EVOLUTION_STAKE_SEED,
+        LEAF_METADATA_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+    },
+    errors::NftAuctionError,
+    state::{
+        CardDefinition,
+        CardSet,
+        CollectionConfig,
+        EvolutionCost,
+        EvolutionStake,
+        LeafArgs,
+        LeafMetadata,
+    },
+    utils::{ card_leaf_hashes, delegate_leaf, BurnLeafAccounts },
+};
+
+#[derive(Accounts)]
+#[instruction(leaf: LeafArgs)]
+pub struct StartEvolution<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Program PDA listed as the verified creator of every card
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Box<Account<'info, CardSet>>,
+
+    #[account(
+        has_one = card_set,
+        constraint = matches!(card_definition.evolution_cost, EvolutionCost::Staking { .. }) @ NftAuctionError::InvalidEvolutionTarget
+    )]
+    pub card_definition: Box<Account<'info, CardDefinition>>,
+
+    // Only for leaves updated on their own by `update_card_metadata`
+    #[account(
+        seeds = [LEAF_METADATA_SEED, merkle_tree.key().as_ref(), &leaf.nonce.to_le_bytes()],
+        bump = leaf_metadata.bump
+    )]
+    pub leaf_metadata: Option<Account<'info, LeafMetadata>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + EvolutionStake::INIT_SPACE,
+        seeds = [
+            EVOLUTION_STAKE_SEED,
+            owner.key().as_ref(),
+            merkle_tree.key().as_ref(),
+            &leaf.nonce.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub evolution_stake: Box<Account<'info, EvolutionStake>>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Current delegate of the staked leaf
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Tree holding the staked leaf
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts: proof nodes of the staked leaf
+pub fn start_evolution<'info>(
+    ctx: Context<'_, '_, '_, 'info, StartEvolution<'info>>,
+    leaf: LeafArgs
+) -> Result<()> {
+    let (data_hash, creator_hash) = card_leaf_hashes(
+        &ctx.accounts.card_definition,
+        &ctx.accounts.collection_config,
+        ctx.accounts.collection_authority.key(),
+        &leaf,
+        ctx.accounts.leaf_metadata.as_deref()
+    )?;
+
+    // Bubblegum checks the proof against the owner; `evolve` then burns with the stake as delegate
+    delegate_leaf(
+        BurnLeafAccounts {
+            tree_authority: &ctx.accounts.tree_authority,
+            leaf_owner: &ctx.accounts.owner,
+            leaf_delegate: &ctx.accounts.leaf_delegate,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.evolution_stake.to_account_info(),
+        &leaf,
+        data_hash,
+        creator_hash,
+        ctx.remaining_accounts
+    )?;
+
+    let evolution_stake = &mut ctx.accounts.evolution_stake;
+    evolution_stake.owner = ctx.accounts.owner.key();
+    evolution_stake.card_definition = ctx.accounts.card_definition.key();
+    evolution_stake.merkle_tree = ctx.accounts.merkle_tree.key();
+    evolution_stake.nonce = leaf.nonce;
+    evolution_stake.started_at = Clock::get()?.unix_timestamp;
+    evolution_stake.version = EvolutionStake::VERSION;
+    evolution_stake.bump = ctx.bumps.evolution_stake;
+
+    Ok(())
+}
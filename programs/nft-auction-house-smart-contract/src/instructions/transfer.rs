@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{ instruction::{ Instruction, AccountMeta }, program::invoke };
 use anchor_spl::{ token::Token, associated_token::AssociatedToken };
-use crate::constants::BUBBLEGUM_PROGRAM_ID;
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID,
+    SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+};
 use crate::errors::NftAuctionError;
 use crate::state::{ NftList, TransferArgs };
 
@@ -17,8 +20,13 @@ pub struct Transfer<'info> {
     #[account(mut)]
     pub nft_list: Account<'info, NftList>,
 
-    /// CHECK:
-    #[account(mut)]
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        mut,
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
     pub tree_authority: UncheckedAccount<'info>,
 
     /// CHECK:
@@ -29,7 +37,8 @@ pub struct Transfer<'info> {
     #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
     pub bubblegum_program: AccountInfo<'info>,
 
-    /// CHECK:
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
     pub compression_program: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
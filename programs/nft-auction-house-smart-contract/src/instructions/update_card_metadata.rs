@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ instruction::{ Instruction, AccountMeta }, program::invoke_signed };
+use anchor_spl::token::Mint;
+use crate::{
+    constants::{
+        BUBBLEGUM_PROGRAM_ID,
+        CARD_SET_SEED,
+        COLLECTION_AUTHORITY_SEED,
+        COLLECTION_CONFIG_SEED,
+        LEAF_METADATA_SEED,
+        ROLES_SEED,
+        SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        SPL_NOOP_PROGRAM_ID,
+        TOKEN_METADATA_PROGRAM_ID,
+    },
+    errors::NftAuctionError,
+    events::CardMetadataUpdated,
+    instructions::mint_nft::{ Creator, MetadataArgs },
+    state::{ CardDefinition, CardSet, CollectionConfig, LeafMetadata, Role, Roles },
+    utils::{ card_metadata_args, hash_metadata, validate_metadata },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateCardMetadataArgs {
+    pub root: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+    pub current_metadata: MetadataArgs,
+    // Card attributes live in the JSON at `uri`, so a new `uri` is how they change
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateArgs {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub creators: Option<Vec<Creator>>,
+    pub seller_fee_basis_points: Option<u16>,
+    pub primary_sale_happened: Option<bool>,
+    pub is_mutable: Option<bool>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateCardMetadataArgs)]
+pub struct UpdateCardMetadata<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, collection_mint.key().as_ref()],
+        bump = card_set.bump,
+        constraint = card_set.merkle_trees.contains(&merkle_tree.key()) @ NftAuctionError::UnknownMerkleTree
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    // Definition the leaf was minted from; it and the leaf's siblings are left unchanged
+    #[account(has_one = card_set)]
+    pub card_definition: Box<Account<'info, CardDefinition>>,
+
+    // Holds the leaf's data hash from now on, so burns, crafts and evolutions can still prove it
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + LeafMetadata::INIT_SPACE,
+        seeds = [LEAF_METADATA_SEED, merkle_tree.key().as_ref(), &args.nonce.to_le_bytes()],
+        bump
+    )]
+    pub leaf_metadata: Box<Account<'info, LeafMetadata>>,
+
+    #[account(
+        seeds = [COLLECTION_CONFIG_SEED, collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    /// CHECK: Program PDA that owns the collection and signs metadata updates
+    #[account(seeds = [COLLECTION_AUTHORITY_SEED], bump)]
+    pub collection_authority: UncheckedAccount<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    /// CHECK: Collection metadata
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config of `merkle_tree`
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = BUBBLEGUM_PROGRAM_ID
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    pub leaf_owner: UncheckedAccount<'info>,
+
+    /// CHECK:
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program
+    #[account(constraint = log_wrapper.key() == SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(constraint = compression_program.key() == SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Token Metadata program
+    #[account(constraint = token_metadata_program.key() == TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(constraint = bubblegum_program.key() == BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_card_metadata<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateCardMetadata<'info>>,
+    args: UpdateCardMetadataArgs
+) -> Result<()> {
+    let leaf_metadata = &mut ctx.accounts.leaf_metadata;
+    let card_definition = ctx.accounts.card_definition.key();
+
+    // The leaf must carry its last recorded metadata, or its definition's if never updated
+    let old_data_hash = hash_metadata(&args.current_metadata)?;
+    let known_data_hash = if leaf_metadata.card_definition == Pubkey::default() {
+        hash_metadata(
+            &card_metadata_args(
+                &ctx.accounts.card_definition,
+                &ctx.accounts.collection_config,
+                ctx.accounts.collection_authority.key()
+            )
+        )?
+    } else {
+        require_keys_eq!(
+            leaf_metadata.card_definition,
+            card_definition,
+            NftAuctionError::InvalidLeafMetadata
+        );
+        leaf_metadata.data_hash
+    };
+    require!(old_data_hash == known_data_hash, NftAuctionError::UnknownMetadataRevision);
+
+    // Only this leaf changes; creators stay as minted, so its creator hash does too
+    let mut new_metadata = args.current_metadata.clone();
+    if let Some(name) = &args.name {
+        new_metadata.name = name.clone();
+    }
+    if let Some(symbol) = &args.symbol {
+        new_metadata.symbol = symbol.clone();
+    }
+    if let Some(uri) = &args.uri {
+        new_metadata.uri = uri.clone();
+    }
+    validate_metadata(&new_metadata.name, &new_metadata.symbol, &new_metadata.uri)?;
+    let new_data_hash = hash_metadata(&new_metadata)?;
+
+    leaf_metadata.version = LeafMetadata::VERSION;
+    leaf_metadata.merkle_tree = ctx.accounts.merkle_tree.key();
+    leaf_metadata.nonce = args.nonce;
+    leaf_metadata.card_definition = card_definition;
+    leaf_metadata.data_hash = new_data_hash;
+    leaf_metadata.bump = ctx.bumps.leaf_metadata;
+
+    let discriminator = anchor_lang::solana_program::hash::hash(b"global:update_metadata").to_bytes();
+    let mut data = discriminator[..8].to_vec();
+
+    #[derive(AnchorSerialize)]
+    struct BubblegumUpdateMetadataArgs {
+        root: [u8; 32],
+        nonce: u64,
+        index: u32,
+        current_metadata: MetadataArgs,
+        update_args: UpdateArgs,
+    }
+
+    let bubblegum_args = BubblegumUpdateMetadataArgs {
+        root: args.root,
+        nonce: args.nonce,
+        index: args.index,
+        current_metadata: args.current_metadata,
+        update_args: UpdateArgs {
+            name: Some(new_metadata.name),
+            symbol: Some(new_metadata.symbol),
+            uri: Some(new_metadata.uri),
+            ..Default::default()
+        },
+    };
+
+    data.extend(bubblegum_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(ctx.accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.collection_authority.key(), true),
+        AccountMeta::new_readonly(ctx.accounts.collection_mint.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.collection_metadata.key(), false),
+        // No collection authority record; Bubblegum treats its own id as None
+        AccountMeta::new_readonly(ctx.accounts.bubblegum_program.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.leaf_owner.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.leaf_delegate.key(), false),
+        AccountMeta::new(ctx.accounts.admin.key(), true),
+        AccountMeta::new(ctx.accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.token_metadata_program.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false)
+    ];
+
+    let mut account_infos = vec![
+        ctx.accounts.tree_authority.to_account_info(),
+        ctx.accounts.collection_authority.to_account_info(),
+        ctx.accounts.collection_mint.to_account_info(),
+        ctx.accounts.collection_metadata.to_account_info(),
+        ctx.accounts.bubblegum_program.to_account_info(),
+        ctx.accounts.leaf_owner.to_account_info(),
+        ctx.accounts.leaf_delegate.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.merkle_tree.to_account_info(),
+        ctx.accounts.log_wrapper.to_account_info(),
+        ctx.accounts.compression_program.to_account_info(),
+        ctx.accounts.token_metadata_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    ];
+
+    // Merkle proof nodes are passed through as remaining accounts
+    for proof_node in ctx.remaining_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(proof_node.key(), false));
+        account_infos.push(proof_node.clone());
+    }
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts,
+        data,
+    };
+
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[COLLECTION_AUTHORITY_SEED, &[ctx.bumps.collection_authority]],
+    ];
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+    emit!(CardMetadataUpdated {
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        nonce: args.nonce,
+        old_data_hash,
+        new_data_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ RARITY_TIERS, ROLES_SEED },
+    errors::NftAuctionError,
+    instructions::create_drop_table::validate_drop_weights,
+    state::{ DropTable, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct UpdateDropTable<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(mut, constraint = !drop_table.activated @ NftAuctionError::DropTableActivated)]
+    pub drop_table: Account<'info, DropTable>,
+}
+
+pub fn update_drop_table(
+    ctx: Context<UpdateDropTable>,
+    rarity_weights: [u16; RARITY_TIERS],
+    pity_threshold: u16,
+    pity_rarity: u8
+) -> Result<()> {
+    validate_drop_weights(&rarity_weights, pity_rarity)?;
+
+    let drop_table = &mut ctx.accounts.drop_table;
+    drop_table.rarity_weights = rarity_weights;
+    drop_table.pity_threshold = pity_threshold;
+    drop_table.pity_rarity = pity_rarity;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ CARD_SET_SEED, MAX_PAYMENT_MINTS, ROLES_SEED },
+    errors::NftAuctionError,
+    state::{ CardSet, PackConfig, PaymentPrice, PurchaseLimits, RandomnessProvider, Role, Roles },
+};
+
+#[derive(Accounts)]
+pub struct UpdatePackConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::PriceManager) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [CARD_SET_SEED, card_set.collection_mint.as_ref()],
+        bump = card_set.bump
+    )]
+    pub card_set: Account<'info, CardSet>,
+
+    #[account(mut, has_one = card_set)]
+    pub pack_config: Account<'info, PackConfig>,
+}
+
+// Only new purchases are affected; pending ones reveal through the provider they were bought with
+pub fn set_randomness_provider(
+    ctx: Context<UpdatePackConfig>,
+    provider: RandomnessProvider
+) -> Result<()> {
+    ctx.accounts.pack_config.randomness_provider = provider;
+    Ok(())
+}
+
+// Adds or reprices an accepted payment mint; `None` stops accepting it
+pub fn set_payment_price(
+    ctx: Context<UpdatePackConfig>,
+    mint: Pubkey,
+    price: Option<u64>
+) -> Result<()> {
+    let payment_prices = &mut ctx.accounts.pack_config.payment_prices;
+    let position = payment_prices.iter().position(|p| p.mint == mint);
+
+    match (position, price) {
+        (Some(position), Some(price)) => {
+            payment_prices[position].price = price;
+        }
+        (Some(position), None) => {
+            payment_prices.remove(position);
+        }
+        (None, Some(price)) => {
+            require!(payment_prices.len() < MAX_PAYMENT_MINTS, NftAuctionError::TooManyPaymentMints);
+            payment_prices.push(PaymentPrice { mint, price });
+        }
+        (None, None) => {}
+    }
+
+    Ok(())
+}
+
+pub fn set_purchase_limits(ctx: Context<UpdatePackConfig>, limits: PurchaseLimits) -> Result<()> {
+    ctx.accounts.pack_config.purchase_limits = limits;
+    Ok(())
+}
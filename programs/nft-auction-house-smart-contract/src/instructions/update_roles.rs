@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::{ constants::ROLES_SEED, errors::NftAuctionError, state::{ Role, Roles } };
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    pub super_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(super_admin.key, Role::SuperAdmin) @
+        NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+pub fn grant_role(ctx: Context<UpdateRoles>, authority: Pubkey, role: Role) -> Result<()> {
+    ctx.accounts.roles.grant(authority, role)
+}
+
+pub fn revoke_role(ctx: Context<UpdateRoles>, authority: Pubkey, role: Role) -> Result<()> {
+    ctx.accounts.roles.revoke(authority, role)
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{ ROLES_SEED, TREASURY_SEED },
+    errors::NftAuctionError,
+    state::{ Role, Roles, Treasury },
+};
+
+#[derive(Accounts)]
+pub struct UpdateTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::SuperAdmin) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+// Amount of `mint` (`SOL_PAYMENT_MINT` for lamports) withdrawable per 24h without the timelock
+pub fn set_treasury_limit(ctx: Context<UpdateTreasury>, mint: Pubkey, daily_cap: u64) -> Result<()> {
+    ctx.accounts.treasury.set_limit(mint, daily_cap)
+}
+
+// Delay of queued withdrawals; never below `MIN_WITHDRAWAL_TIMELOCK_SECS`
+pub fn set_treasury_timelock(ctx: Context<UpdateTreasury>, timelock: i64) -> Result<()> {
+    ctx.accounts.treasury.set_timelock(timelock)
+}
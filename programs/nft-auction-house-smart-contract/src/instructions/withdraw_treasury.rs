@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface };
+use crate::{
+    constants::{ ROLES_SEED, SOL_PAYMENT_MINT, TREASURY_SEED },
+    errors::NftAuctionError,
+    state::{ Role, Roles, Treasury },
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [ROLES_SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(admin.key, Role::Treasurer) @ NftAuctionError::MissingRole
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    // Omitted to withdraw SOL
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Receiving wallet for SOL, token account of `mint` otherwise
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+// Moves `amount` out of the treasury; shared with `execute_withdrawal`
+pub fn transfer_from_treasury<'info>(
+    treasury: &Account<'info, Treasury>,
+    mint: Option<&InterfaceAccount<'info, Mint>>,
+    treasury_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    destination: &AccountInfo<'info>,
+    token_program: Option<&Interface<'info, TokenInterface>>,
+    amount: u64
+) -> Result<()> {
+    match mint {
+        None => {
+            // The treasury holds data, so lamports move directly and it must stay rent exempt
+            let treasury_info = treasury.to_account_info();
+            let rent_floor = Rent::get()?.minimum_balance(treasury_info.data_len());
+            require!(
+                treasury_info.lamports().saturating_sub(amount) >= rent_floor,
+                NftAuctionError::InsufficientFunds
+            );
+
+            **treasury_info.try_borrow_mut_lamports()? -= amount;
+            **destination.try_borrow_mut_lamports()? += amount;
+        }
+        Some(mint) => {
+            let treasury_token_account = treasury_token_account.ok_or(
+                NftAuctionError::MissingVaultAta
+            )?;
+            let token_program = token_program.ok_or(NftAuctionError::InvalidPaymentAccount)?;
+            let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, &[treasury.bump]]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: treasury_token_account.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: destination.clone(),
+                        authority: treasury.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                amount,
+                mint.decimals
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    let mint = ctx.accounts.mint
+        .as_ref()
+        .map(|mint| mint.key())
+        .unwrap_or(SOL_PAYMENT_MINT);
+    ctx.accounts.treasury.consume_limit(mint, amount, Clock::get()?.unix_timestamp)?;
+
+    transfer_from_treasury(
+        &ctx.accounts.treasury,
+        ctx.accounts.mint.as_deref(),
+        ctx.accounts.treasury_token_account.as_deref(),
+        &ctx.accounts.destination.to_account_info(),
+        ctx.accounts.token_program.as_ref(),
+        amount
+    )
+}
@@ -1,13 +1,28 @@
 use anchor_lang::prelude::*;
 
 pub mod constants;
+pub mod draw;
 pub mod errors;
+pub mod events;
+pub mod fees;
 pub mod instructions;
 pub mod misc;
+pub mod oracle;
+pub mod randomness;
 pub mod state;
 pub mod utils;
 
-use crate::state::TransferArgs;
+use crate::constants::RARITY_TIERS;
+use crate::state::{
+    EvolutionCost,
+    LeafArgs,
+    PoolEntry,
+    PurchaseLimits,
+    RandomnessProvider,
+    Role,
+    RoyaltyCreator,
+    TransferArgs,
+};
 use instructions::*;
 
 declare_id!("988hJvsyGwL2TM5WRGKdAxWAUvWx97dDQANPiRt5Mukn");
@@ -25,25 +40,336 @@ pub mod nft_auction_house_smart_contract {
         Ok(())
     }
 
-    // Create collection NFT (call this once per collection)
+    // Bring a config created by an older program version up to the current layout
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        migrate_config::migrate_config(ctx)?;
+        Ok(())
+    }
+
+    // Same for every other versioned account, one account per call
+    pub fn migrate_account(ctx: Context<MigrateAccount>, kind: VersionedAccount) -> Result<()> {
+        migrate_account::migrate_account(ctx, kind)?;
+        Ok(())
+    }
+
+    // Roles account; the config admin starts as its only super admin
+    pub fn init_roles(ctx: Context<InitRoles>) -> Result<()> {
+        init_roles::init_roles(ctx)?;
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<UpdateRoles>, authority: Pubkey, role: Role) -> Result<()> {
+        update_roles::grant_role(ctx, authority, role)?;
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<UpdateRoles>, authority: Pubkey, role: Role) -> Result<()> {
+        update_roles::revoke_role(ctx, authority, role)?;
+        Ok(())
+    }
+
+    // Set the wallets and basis points every payment is split between; the treasury share goes
+    // to the program-owned treasury
+    pub fn set_fee_schedule(
+        ctx: Context<SetFeeSchedule>,
+        creator: Pubkey,
+        partner: Pubkey,
+        shares_bps: [u16; 4]
+    ) -> Result<()> {
+        set_fee_schedule::set_fee_schedule(ctx, creator, partner, shares_bps)?;
+        Ok(())
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        register_referrer::register_referrer(ctx)?;
+        Ok(())
+    }
+
+    // Pay out a referrer's accrued balance of one mint (omit the mint for SOL)
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        claim_referral::claim_referral(ctx)?;
+        Ok(())
+    }
+
+    // Program-owned treasury that fee schedule revenue can be routed to
+    pub fn init_treasury(ctx: Context<InitTreasury>, timelock: i64) -> Result<()> {
+        init_treasury::init_treasury(ctx, timelock)?;
+        Ok(())
+    }
+
+    // Super admin only, since the caps bound what the Treasurer can move without the timelock
+    pub fn set_treasury_limit(
+        ctx: Context<UpdateTreasury>,
+        mint: Pubkey,
+        daily_cap: u64
+    ) -> Result<()> {
+        update_treasury::set_treasury_limit(ctx, mint, daily_cap)?;
+        Ok(())
+    }
+
+    // Super admin only; the timelock can't go below `MIN_WITHDRAWAL_TIMELOCK_SECS`
+    pub fn set_treasury_timelock(ctx: Context<UpdateTreasury>, timelock: i64) -> Result<()> {
+        update_treasury::set_treasury_timelock(ctx, timelock)?;
+        Ok(())
+    }
+
+    // Withdraw within the rolling 24h cap; larger amounts go through `queue_withdrawal`
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        withdraw_treasury::withdraw_treasury(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn queue_withdrawal(
+        ctx: Context<QueueWithdrawal>,
+        mint: Pubkey,
+        amount: u64,
+        destination: Pubkey
+    ) -> Result<()> {
+        queue_withdrawal::queue_withdrawal(ctx, mint, amount, destination)?;
+        Ok(())
+    }
+
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+        execute_withdrawal::execute_withdrawal(ctx)?;
+        Ok(())
+    }
+
+    // Guardian veto of a queued withdrawal
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+        cancel_withdrawal::cancel_withdrawal(ctx)?;
+        Ok(())
+    }
+
+    // Top-up share, target and pause ratios of the reserve that funds `instant_sell`
+    pub fn configure_buyback_reserve(
+        ctx: Context<ConfigureBuybackReserve>,
+        top_up_bps: u16,
+        target_ratio_bps: u16,
+        pause_ratio_bps: u16,
+        paused: bool
+    ) -> Result<()> {
+        configure_buyback_reserve::configure_buyback_reserve(
+            ctx,
+            top_up_bps,
+            target_ratio_bps,
+            pause_ratio_bps,
+            paused
+        )?;
+        Ok(())
+    }
+
+    pub fn fund_buyback_reserve(ctx: Context<FundBuybackReserve>, amount: u64) -> Result<()> {
+        fund_buyback_reserve::fund_buyback_reserve(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn set_buybacks_paused(ctx: Context<PauseBuybacks>, paused: bool) -> Result<()> {
+        configure_buyback_reserve::set_buybacks_paused(ctx, paused)?;
+        Ok(())
+    }
+
+    // Create collection NFT (call this once per collection); up to four royalty creators
     pub fn create_collection(
         ctx: Context<CreateCollection>,
         name: String,
         symbol: String,
-        uri: String
+        uri: String,
+        seller_fee_basis_points: u16,
+        creators: Vec<RoyaltyCreator>
     ) -> Result<()> {
-        mint_nft::create_collection(ctx, name, symbol, uri)?;
+        mint_nft::create_collection(ctx, name, symbol, uri, seller_fee_basis_points, creators)?;
+        Ok(())
+    }
+
+    // Register a Bubblegum tree that cards of this set may be minted into
+    pub fn add_merkle_tree(ctx: Context<AddMerkleTree>) -> Result<()> {
+        add_merkle_tree::add_merkle_tree(ctx)?;
         Ok(())
     }
 
-    // Mint cNFT to existing collection (can be called multiple times)
-    pub fn mint_nft(
-        ctx: Context<MintCnft>,
+    pub fn add_card_definition(
+        ctx: Context<AddCardDefinition>,
+        rarity: u8,
+        index: u16,
+        pack_id: u8,
         name: String,
         symbol: String,
         uri: String
     ) -> Result<()> {
-        mint_nft::cpi_mint_cnft(ctx, name, symbol, uri)?;
+        add_card_definition::add_card_definition(ctx, rarity, index, pack_id, name, symbol, uri)?;
+        Ok(())
+    }
+
+    pub fn set_pack_config(
+        ctx: Context<SetPackConfig>,
+        pack_id: u8,
+        price: u64,
+        cards_per_pack: u8
+    ) -> Result<()> {
+        set_pack_config::set_pack_config(ctx, pack_id, price, cards_per_pack)?;
+        Ok(())
+    }
+
+    // Switch the randomness source of a pack config, e.g. while an oracle is down
+    pub fn set_randomness_provider(
+        ctx: Context<UpdatePackConfig>,
+        provider: RandomnessProvider
+    ) -> Result<()> {
+        update_pack_config::set_randomness_provider(ctx, provider)?;
+        Ok(())
+    }
+
+    // Accept another payment mint for a pack config (`Pubkey::default()` for SOL)
+    pub fn set_payment_price(
+        ctx: Context<UpdatePackConfig>,
+        mint: Pubkey,
+        price: Option<u64>
+    ) -> Result<()> {
+        update_pack_config::set_payment_price(ctx, mint, price)?;
+        Ok(())
+    }
+
+    // Per-wallet caps and cooldown for a pack config; zero fields are unlimited
+    pub fn set_purchase_limits(
+        ctx: Context<UpdatePackConfig>,
+        limits: PurchaseLimits
+    ) -> Result<()> {
+        update_pack_config::set_purchase_limits(ctx, limits)?;
+        Ok(())
+    }
+
+    pub fn create_drop_table(
+        ctx: Context<CreateDropTable>,
+        version: u32,
+        rarity_weights: [u16; RARITY_TIERS],
+        pity_threshold: u16,
+        pity_rarity: u8
+    ) -> Result<()> {
+        create_drop_table::create_drop_table(
+            ctx,
+            version,
+            rarity_weights,
+            pity_threshold,
+            pity_rarity
+        )?;
+        Ok(())
+    }
+
+    pub fn update_drop_table(
+        ctx: Context<UpdateDropTable>,
+        rarity_weights: [u16; RARITY_TIERS],
+        pity_threshold: u16,
+        pity_rarity: u8
+    ) -> Result<()> {
+        update_drop_table::update_drop_table(ctx, rarity_weights, pity_threshold, pity_rarity)?;
+        Ok(())
+    }
+
+    // Freeze a drop table and make it the odds for new purchases of its pack config
+    pub fn activate_drop_table(ctx: Context<ActivateDropTable>) -> Result<()> {
+        activate_drop_table::activate_drop_table(ctx)?;
+        Ok(())
+    }
+
+    // Switch a pack config to a finite, box-break style card pool
+    pub fn create_pack_pool(ctx: Context<CreatePackPool>) -> Result<()> {
+        create_pack_pool::create_pack_pool(ctx)?;
+        Ok(())
+    }
+
+    pub fn add_pool_cards(ctx: Context<AddPoolCards>, cards: Vec<PoolEntry>) -> Result<()> {
+        add_pool_cards::add_pool_cards(ctx, cards)?;
+        Ok(())
+    }
+
+    // Mint a cNFT of a card definition into its set's collection (can be called multiple times)
+    pub fn mint_nft(ctx: Context<MintCnft>) -> Result<()> {
+        mint_nft::cpi_mint_cnft(ctx)?;
+        Ok(())
+    }
+
+    // Rewrite name/symbol/uri of one minted card leaf (artwork fixes, evolutions, levels); other
+    // leaves of the same card keep the definition's metadata
+    pub fn update_card_metadata<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCardMetadata<'info>>,
+        args: UpdateCardMetadataArgs
+    ) -> Result<()> {
+        update_card_metadata::update_card_metadata(ctx, args)?;
+        Ok(())
+    }
+
+    pub fn set_burn_rewards(
+        ctx: Context<SetBurnRewards>,
+        rewards: [u64; RARITY_TIERS]
+    ) -> Result<()> {
+        set_burn_rewards::set_burn_rewards(ctx, rewards)?;
+        Ok(())
+    }
+
+    pub fn set_craft_input_count(
+        ctx: Context<SetCraftInputCount>,
+        craft_input_count: u8
+    ) -> Result<()> {
+        set_craft_input_count::set_craft_input_count(ctx, craft_input_count)?;
+        Ok(())
+    }
+
+    // Burn a card leaf owned by the signer, paying the tier's USDC reward if one is set
+    pub fn burn_card<'info>(
+        ctx: Context<'_, '_, '_, 'info, BurnCard<'info>>,
+        leaf: LeafArgs
+    ) -> Result<()> {
+        burn_card::burn_card(ctx, leaf)?;
+        Ok(())
+    }
+
+    // Burn same-tier duplicates and request randomness for a card of the next tier
+    pub fn craft<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Craft<'info>>,
+        force: [u8; 32],
+        inputs: Vec<CraftInput>
+    ) -> Result<()> {
+        craft::craft(ctx, force, inputs)?;
+        Ok(())
+    }
+
+    pub fn fulfill_craft(ctx: Context<FulfillCraft>, force: [u8; 32]) -> Result<()> {
+        fulfill_craft::fulfill_craft(ctx, force)?;
+        Ok(())
+    }
+
+    // Mint the revealed crafted card to the crafter
+    pub fn claim_crafted_card(ctx: Context<ClaimCraftedCard>, force: [u8; 32]) -> Result<()> {
+        claim_crafted_card::claim_crafted_card(ctx, force)?;
+        Ok(())
+    }
+
+    // Link a card definition to the card it evolves into; omit the target to clear it
+    pub fn set_evolution(ctx: Context<SetEvolution>, cost: EvolutionCost) -> Result<()> {
+        set_evolution::set_evolution(ctx, cost)?;
+        Ok(())
+    }
+
+    // Stake a leaf by delegating it to its evolution stake; remaining accounts are its proof
+    pub fn start_evolution<'info>(
+        ctx: Context<'_, '_, '_, 'info, StartEvolution<'info>>,
+        leaf: LeafArgs
+    ) -> Result<()> {
+        start_evolution::start_evolution(ctx, leaf)?;
+        Ok(())
+    }
+
+    pub fn evolve<'info>(
+        ctx: Context<'_, '_, '_, 'info, Evolve<'info>>,
+        args: EvolveArgs
+    ) -> Result<()> {
+        evolve::evolve(ctx, args)?;
+        Ok(())
+    }
+
+    // Mint the evolved card recorded by `evolve` to its owner
+    pub fn claim_evolved_card(ctx: Context<ClaimEvolvedCard>) -> Result<()> {
+        claim_evolved_card::claim_evolved_card(ctx)?;
         Ok(())
     }
 
@@ -51,9 +377,10 @@ pub mod nft_auction_house_smart_contract {
         ctx: Context<BuyPack>,
         force: [u8; 32],
         pack_id: u8,
-        pack_count: u8
+        pack_count: u8,
+        max_payment: Option<u64>
     ) -> Result<()> {
-        buy_pack::buy_pack(ctx, force, pack_id, pack_count)?;
+        buy_pack::buy_pack(ctx, force, pack_id, pack_count, max_payment)?;
         Ok(())
     }
 
@@ -62,17 +389,38 @@ pub mod nft_auction_house_smart_contract {
         Ok(())
     }
 
+    // Guardian: new randomness for a purchase whose request can no longer be revealed
+    pub fn rerequest_randomness(
+        ctx: Context<RerequestRandomness>,
+        force: [u8; 32],
+        request_seed: [u8; 32]
+    ) -> Result<()> {
+        rerequest_randomness::rerequest_randomness(ctx, force, request_seed)?;
+        Ok(())
+    }
+
+    // Test builds only: set the value of a mock randomness account
+    #[cfg(feature = "mock-vrf")]
+    pub fn mock_fulfill(
+        ctx: Context<MockFulfill>,
+        force: [u8; 32],
+        randomness: [u8; 64]
+    ) -> Result<()> {
+        mock_fulfill::mock_fulfill(ctx, force, randomness)?;
+        Ok(())
+    }
+
     pub fn transfer(ctx: Context<Transfer>, transfer_args: TransferArgs) -> Result<()> {
         transfer::transfer(ctx, transfer_args)?;
         Ok(())
     }
 
-    pub fn instant_sell(
-        ctx: Context<InstantSell>,
-        pack_id: u8,
-        transfer_args: TransferArgs
+    // Sell a card back to the platform vault for the reserve-scaled buyback price
+    pub fn instant_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, InstantSell<'info>>,
+        leaf: LeafArgs
     ) -> Result<()> {
-        instant_sell::instant_sell(ctx, pack_id, transfer_args)?;
+        instant_sell::instant_sell(ctx, leaf)?;
         Ok(())
     }
 
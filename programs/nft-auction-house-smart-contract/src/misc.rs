@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use orao_solana_vrf::state::RandomnessAccountData;
+use crate::errors::NftAuctionError;
+
+pub fn get_account_data(account_info: &AccountInfo) -> Result<RandomnessAccountData> {
+    if account_info.data_is_empty() {
+        return Err(NftAuctionError::StillProcessing.into());
+    }
+
+    let account = RandomnessAccountData::try_deserialize(&mut &account_info.data.borrow()[..])?;
+    Ok(account)
+}
+
+// First 8 bytes of the fulfilled randomness, or 0 while the request is pending
+pub fn current_state(randomness: &RandomnessAccountData) -> u64 {
+    match fulfilled_randomness(randomness) {
+        Some(value) => u64::from_le_bytes(value[0..8].try_into().unwrap()),
+        None => 0,
+    }
+}
+
+pub fn fulfilled_randomness(randomness: &RandomnessAccountData) -> Option<[u8; 64]> {
+    randomness.fulfilled_randomness().copied()
+}
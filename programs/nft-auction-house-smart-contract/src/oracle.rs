@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::constants::{
+    MAX_PRICE_AGE_SECS,
+    MAX_PRICE_CONFIDENCE_BPS,
+    PYTH_RECEIVER_PROGRAM_ID,
+    SOL_USD_FEED_ID,
+};
+use crate::errors::NftAuctionError;
+
+// Mirrors of the Pyth receiver's `PriceUpdateV2` account, which is parsed by hand to avoid the
+// pyth-solana-receiver-sdk dependency. Local tests can load an account with this layout owned by
+// the receiver program as a mock price feed.
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial {
+        _num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+pub struct OraclePrice {
+    pub price: u64,
+    pub exponent: i32,
+}
+
+// Fully verified, fresh SOL/USD price with a confidence interval inside the configured bound
+pub fn load_sol_usd_price(price_update: &AccountInfo) -> Result<OraclePrice> {
+    let data = price_update.try_borrow_data()?;
+    parse_sol_usd_price(price_update.owner, &data, Clock::get()?.unix_timestamp)
+}
+
+fn parse_sol_usd_price(owner: &Pubkey, data: &[u8], now: i64) -> Result<OraclePrice> {
+    require_keys_eq!(*owner, PYTH_RECEIVER_PROGRAM_ID, NftAuctionError::InvalidPriceAccount);
+
+    let discriminator = hash(b"account:PriceUpdateV2").to_bytes();
+    require!(
+        data.len() > 8 && data[..8] == discriminator[..8],
+        NftAuctionError::InvalidPriceAccount
+    );
+
+    let update = PriceUpdateV2::deserialize(&mut &data[8..]).map_err(
+        |_| error!(NftAuctionError::InvalidPriceAccount)
+    )?;
+    let message = update.price_message;
+    require!(
+        matches!(update.verification_level, VerificationLevel::Full) &&
+            message.feed_id == SOL_USD_FEED_ID &&
+            message.price > 0,
+        NftAuctionError::InvalidPriceAccount
+    );
+
+    require!(
+        now.saturating_sub(message.publish_time) <= MAX_PRICE_AGE_SECS,
+        NftAuctionError::StalePrice
+    );
+
+    let price = message.price as u64;
+    require!(
+        (message.conf as u128) * 10000 <= (price as u128) * (MAX_PRICE_CONFIDENCE_BPS as u128),
+        NftAuctionError::PriceConfidenceTooWide
+    );
+
+    Ok(OraclePrice {
+        price,
+        exponent: message.exponent,
+    })
+}
+
+// Lamports worth `usd_amount` micro-USD (USDC base units):
+// usd_amount * 10^-6 / (price * 10^exponent) * 10^9
+pub fn usd_to_lamports(usd_amount: u64, sol_usd: &OraclePrice) -> Result<u64> {
+    let scale = 3 - sol_usd.exponent;
+    let numerator = if scale >= 0 {
+        10u128
+            .checked_pow(scale as u32)
+            .and_then(|factor| (usd_amount as u128).checked_mul(factor))
+    } else {
+        10u128.checked_pow(-scale as u32).map(|factor| (usd_amount as u128) / factor)
+    };
+
+    let numerator = numerator.ok_or(NftAuctionError::InvalidPriceAccount)?;
+    let lamports = numerator / (sol_usd.price as u128);
+    u64::try_from(lamports).map_err(|_| error!(NftAuctionError::InvalidPriceAccount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+    // $150.00000000 per SOL at Pyth's usual exponent
+    const PRICE: i64 = 15_000_000_000;
+
+    struct Update {
+        verification: Option<u8>,
+        feed_id: [u8; 32],
+        price: i64,
+        conf: u64,
+        publish_time: i64,
+    }
+
+    impl Default for Update {
+        fn default() -> Self {
+            Update {
+                verification: None,
+                feed_id: SOL_USD_FEED_ID,
+                price: PRICE,
+                conf: 1_000_000,
+                publish_time: NOW,
+            }
+        }
+    }
+
+    // `PriceUpdateV2` as the receiver program writes it; `verification` of `Some(n)` is Partial
+    fn price_update_data(update: &Update) -> Vec<u8> {
+        let mut data = hash(b"account:PriceUpdateV2").to_bytes()[..8].to_vec();
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        match update.verification {
+            Some(num_signatures) => data.extend_from_slice(&[0, num_signatures]),
+            None => data.push(1),
+        }
+        data.extend_from_slice(&update.feed_id);
+        data.extend_from_slice(&update.price.to_le_bytes());
+        data.extend_from_slice(&update.conf.to_le_bytes());
+        data.extend_from_slice(&(-8i32).to_le_bytes());
+        data.extend_from_slice(&update.publish_time.to_le_bytes());
+        data.extend_from_slice(&(update.publish_time - 1).to_le_bytes());
+        data.extend_from_slice(&update.price.to_le_bytes());
+        data.extend_from_slice(&update.conf.to_le_bytes());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data
+    }
+
+    fn parse(update: &Update) -> Result<OraclePrice> {
+        parse_sol_usd_price(&PYTH_RECEIVER_PROGRAM_ID, &price_update_data(update), NOW)
+    }
+
+    fn error_code<T>(result: Result<T>) -> u32 {
+        match result {
+            Err(Error::AnchorError(error)) => error.error_code_number,
+            _ => panic!("expected an anchor error"),
+        }
+    }
+
+    #[test]
+    fn usd_to_lamports_with_negative_exponent() {
+        let sol_usd = OraclePrice { price: PRICE as u64, exponent: -8 };
+
+        assert_eq!(usd_to_lamports(150_000_000, &sol_usd).unwrap(), 1_000_000_000);
+        assert_eq!(usd_to_lamports(1_500_000, &sol_usd).unwrap(), 10_000_000);
+        // Rounds down to the lamport
+        assert_eq!(usd_to_lamports(1, &sol_usd).unwrap(), 6);
+    }
+
+    #[test]
+    fn usd_to_lamports_with_positive_exponent() {
+        // $100 per SOL as 1 * 10^2
+        let sol_usd = OraclePrice { price: 1, exponent: 2 };
+        assert_eq!(usd_to_lamports(100_000_000, &sol_usd).unwrap(), 1_000_000_000);
+
+        // $100000 per SOL as 1 * 10^5, so the amount is scaled down instead of up
+        let sol_usd = OraclePrice { price: 1, exponent: 5 };
+        assert_eq!(usd_to_lamports(100_000_000_000, &sol_usd).unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn usd_to_lamports_rejects_overflow() {
+        let tiny_price = OraclePrice { price: 1, exponent: -8 };
+        assert_eq!(
+            error_code(usd_to_lamports(u64::MAX, &tiny_price)),
+            u32::from(NftAuctionError::InvalidPriceAccount)
+        );
+
+        let huge_scale = OraclePrice { price: 1, exponent: -40 };
+        assert_eq!(
+            error_code(usd_to_lamports(1, &huge_scale)),
+            u32::from(NftAuctionError::InvalidPriceAccount)
+        );
+    }
+
+    #[test]
+    fn parses_fresh_verified_price() {
+        let price = parse(&Update::default()).unwrap();
+        assert_eq!(price.price, PRICE as u64);
+        assert_eq!(price.exponent, -8);
+    }
+
+    #[test]
+    fn rejects_stale_price() {
+        let update = Update { publish_time: NOW - MAX_PRICE_AGE_SECS - 1, ..Default::default() };
+        assert_eq!(error_code(parse(&update)), u32::from(NftAuctionError::StalePrice));
+
+        let update = Update { publish_time: NOW - MAX_PRICE_AGE_SECS, ..Default::default() };
+        assert!(parse(&update).is_ok());
+    }
+
+    #[test]
+    fn rejects_wide_confidence() {
+        let max_conf = (PRICE as u64) * MAX_PRICE_CONFIDENCE_BPS / 10000;
+
+        let update = Update { conf: max_conf + 1, ..Default::default() };
+        assert_eq!(error_code(parse(&update)), u32::from(NftAuctionError::PriceConfidenceTooWide));
+
+        let update = Update { conf: max_conf, ..Default::default() };
+        assert!(parse(&update).is_ok());
+    }
+
+    #[test]
+    fn rejects_partial_verification() {
+        let update = Update { verification: Some(3), ..Default::default() };
+        assert_eq!(error_code(parse(&update)), u32::from(NftAuctionError::InvalidPriceAccount));
+    }
+
+    #[test]
+    fn rejects_wrong_feed_id() {
+        let update = Update { feed_id: [1u8; 32], ..Default::default() };
+        assert_eq!(error_code(parse(&update)), u32::from(NftAuctionError::InvalidPriceAccount));
+    }
+
+    #[test]
+    fn rejects_wrong_owner() {
+        let data = price_update_data(&Update::default());
+        assert_eq!(
+            error_code(parse_sol_usd_price(&Pubkey::new_unique(), &data, NOW)),
+            u32::from(NftAuctionError::InvalidPriceAccount)
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ hash::hashv, sysvar::slot_hashes };
+use orao_solana_vrf::{ state::NetworkState, CONFIG_ACCOUNT_SEED, RANDOMNESS_ACCOUNT_SEED };
+use crate::constants::{ SLOT_HASH_REVEAL_DELAY, SWITCHBOARD_ON_DEMAND_PROGRAM_ID };
+use crate::errors::NftAuctionError;
+use crate::misc::{ fulfilled_randomness, get_account_data };
+use crate::state::RandomnessProvider;
+#[cfg(feature = "mock-vrf")]
+use anchor_lang::system_program;
+#[cfg(feature = "mock-vrf")]
+use crate::constants::MOCK_RANDOMNESS_SEED;
+#[cfg(feature = "mock-vrf")]
+use crate::state::MockRandomness;
+
+// Accounts a provider may need to start a request; ORAO-only accounts are optional
+pub struct RandomnessRequest<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub random: &'a AccountInfo<'info>,
+    pub network_state: Option<AccountInfo<'info>>,
+    pub treasury: Option<AccountInfo<'info>>,
+    pub vrf_program: Option<AccountInfo<'info>>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// A source of 64 bytes of randomness for pack and craft reveals.
+///
+/// `request` runs when the purchase is made and returns the slot the request is committed to.
+/// `reveal` runs on fulfillment against the same randomness account and returns `None` while
+/// the value is still pending.
+pub trait RandomnessSource {
+    fn request(accounts: &RandomnessRequest<'_, '_>, force: [u8; 32]) -> Result<u64>;
+
+    fn reveal(random: &AccountInfo, force: &[u8; 32], commit_slot: u64) -> Result<Option<[u8; 64]>>;
+}
+
+#[cfg(not(feature = "mock-vrf"))]
+pub fn request_randomness(
+    provider: RandomnessProvider,
+    accounts: &RandomnessRequest<'_, '_>,
+    force: [u8; 32]
+) -> Result<u64> {
+    match provider {
+        RandomnessProvider::Orao => OraoVrf::request(accounts, force),
+        RandomnessProvider::Switchboard => SwitchboardOnDemand::request(accounts, force),
+        RandomnessProvider::SlotHash => SlotHashCommitReveal::request(accounts, force),
+    }
+}
+
+#[cfg(not(feature = "mock-vrf"))]
+pub fn reveal_randomness(
+    provider: RandomnessProvider,
+    random: &AccountInfo,
+    force: &[u8; 32],
+    commit_slot: u64
+) -> Result<Option<[u8; 64]>> {
+    match provider {
+        RandomnessProvider::Orao => OraoVrf::reveal(random, force, commit_slot),
+        RandomnessProvider::Switchboard => SwitchboardOnDemand::reveal(random, force, commit_slot),
+        RandomnessProvider::SlotHash => SlotHashCommitReveal::reveal(random, force, commit_slot),
+    }
+}
+
+// With `mock-vrf` every provider is replaced by the program-owned mock
+#[cfg(feature = "mock-vrf")]
+pub fn request_randomness(
+    _provider: RandomnessProvider,
+    accounts: &RandomnessRequest<'_, '_>,
+    force: [u8; 32]
+) -> Result<u64> {
+    MockVrf::request(accounts, force)
+}
+
+#[cfg(feature = "mock-vrf")]
+pub fn reveal_randomness(
+    _provider: RandomnessProvider,
+    random: &AccountInfo,
+    force: &[u8; 32],
+    commit_slot: u64
+) -> Result<Option<[u8; 64]>> {
+    MockVrf::reveal(random, force, commit_slot)
+}
+
+// Extra binding seed: per-request randomness accounts are bound on their own, while the shared
+// SlotHashes sysvar is bound per `force`
+pub fn binding_seed<'a>(random: &Pubkey, force: &'a [u8; 32]) -> &'a [u8] {
+    if *random == slot_hashes::ID { force } else { &[] }
+}
+
+// Stretches a 32 byte value into the 64 bytes the draw expects
+fn expand_entropy(value: &[u8], force: &[u8; 32]) -> [u8; 64] {
+    let mut randomness = [0u8; 64];
+    randomness[..32].copy_from_slice(&hashv(&[value, force, &[0]]).to_bytes());
+    randomness[32..].copy_from_slice(&hashv(&[value, force, &[1]]).to_bytes());
+    randomness
+}
+
+pub struct OraoVrf;
+
+impl RandomnessSource for OraoVrf {
+    fn request(accounts: &RandomnessRequest<'_, '_>, force: [u8; 32]) -> Result<u64> {
+        let (expected_random, _) = Pubkey::find_program_address(
+            &[RANDOMNESS_ACCOUNT_SEED, &force],
+            &orao_solana_vrf::ID
+        );
+        require_keys_eq!(
+            accounts.random.key(),
+            expected_random,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        let network_state = accounts.network_state
+            .clone()
+            .ok_or(NftAuctionError::MissingRandomnessAccounts)?;
+        let treasury = accounts.treasury.clone().ok_or(NftAuctionError::MissingRandomnessAccounts)?;
+        let vrf_program = accounts.vrf_program
+            .clone()
+            .ok_or(NftAuctionError::MissingRandomnessAccounts)?;
+
+        let (expected_network_state, _) = Pubkey::find_program_address(
+            &[CONFIG_ACCOUNT_SEED],
+            &orao_solana_vrf::ID
+        );
+        require_keys_eq!(
+            network_state.key(),
+            expected_network_state,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+        require_keys_eq!(vrf_program.key(), orao_solana_vrf::ID, NftAuctionError::InvalidRandomnessAccount);
+
+        // Only the treasury configured in ORAO's network state may receive the request fee
+        let configured_treasury = {
+            let data = network_state.try_borrow_data()?;
+            NetworkState::try_deserialize(&mut &data[..])?.config.treasury
+        };
+        require_keys_eq!(
+            treasury.key(),
+            configured_treasury,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        let cpi_accounts = orao_solana_vrf::cpi::accounts::RequestV2 {
+            payer: accounts.payer.clone(),
+            network_state,
+            treasury,
+            request: accounts.random.clone(),
+            system_program: accounts.system_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new(vrf_program, cpi_accounts);
+        orao_solana_vrf::cpi::request_v2(cpi_ctx, force)?;
+
+        Ok(Clock::get()?.slot)
+    }
+
+    fn reveal(random: &AccountInfo, _force: &[u8; 32], _commit_slot: u64) -> Result<Option<[u8; 64]>> {
+        require_keys_eq!(*random.owner, orao_solana_vrf::ID, NftAuctionError::InvalidRandomnessAccount);
+
+        let rand_acc = get_account_data(random)?;
+        Ok(fulfilled_randomness(&rand_acc))
+    }
+}
+
+// Switchboard On-Demand randomness accounts are zero-copy; only the fields read here are mapped:
+// authority (32) | queue (32) | seed_slothash (32) | seed_slot (8) | oracle (32) | reveal_slot (8) | value (32)
+const SB_SEED_SLOT_OFFSET: usize = 8 + 32 + 32 + 32;
+const SB_REVEAL_SLOT_OFFSET: usize = SB_SEED_SLOT_OFFSET + 8 + 32;
+const SB_VALUE_OFFSET: usize = SB_REVEAL_SLOT_OFFSET + 8;
+const SB_MIN_LEN: usize = SB_VALUE_OFFSET + 32;
+
+pub struct SwitchboardOnDemand;
+
+impl SwitchboardOnDemand {
+    fn read_u64(data: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn load(random: &AccountInfo) -> Result<(u64, u64, [u8; 32])> {
+        require_keys_eq!(
+            *random.owner,
+            SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        let data = random.try_borrow_data()?;
+        require!(data.len() >= SB_MIN_LEN, NftAuctionError::InvalidRandomnessAccount);
+
+        let mut value = [0u8; 32];
+        value.copy_from_slice(&data[SB_VALUE_OFFSET..SB_VALUE_OFFSET + 32]);
+
+        Ok((
+            Self::read_u64(&data, SB_SEED_SLOT_OFFSET),
+            Self::read_u64(&data, SB_REVEAL_SLOT_OFFSET),
+            value,
+        ))
+    }
+}
+
+impl RandomnessSource for SwitchboardOnDemand {
+    // The client commits the randomness account in the slot before the purchase. `force` must be
+    // derived from the account and its commit, so each commit can be bound to one request only.
+    fn request(accounts: &RandomnessRequest<'_, '_>, force: [u8; 32]) -> Result<u64> {
+        let (seed_slot, reveal_slot, _) = Self::load(accounts.random)?;
+        let current_slot = Clock::get()?.slot;
+
+        require!(
+            reveal_slot == 0 && seed_slot == current_slot.saturating_sub(1),
+            NftAuctionError::InvalidRandomnessAccount
+        );
+        require!(
+            force == hashv(&[accounts.random.key().as_ref(), &seed_slot.to_le_bytes()]).to_bytes(),
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        Ok(seed_slot)
+    }
+
+    // Like the Switchboard SDK, only a value revealed in the current slot is accepted
+    fn reveal(random: &AccountInfo, force: &[u8; 32], commit_slot: u64) -> Result<Option<[u8; 64]>> {
+        let (seed_slot, reveal_slot, value) = Self::load(random)?;
+        require!(seed_slot == commit_slot, NftAuctionError::InvalidRandomnessAccount);
+
+        if reveal_slot <= commit_slot {
+            return Ok(None);
+        }
+        require!(reveal_slot == Clock::get()?.slot, NftAuctionError::RandomnessExpired);
+
+        Ok(Some(expand_entropy(&value, force)))
+    }
+}
+
+// Fallback when no oracle is reachable: the hash of a slot a few slots after the purchase.
+// The leader of that slot can bias it, so it suits low-value packs only.
+pub struct SlotHashCommitReveal;
+
+impl RandomnessSource for SlotHashCommitReveal {
+    fn request(accounts: &RandomnessRequest<'_, '_>, _force: [u8; 32]) -> Result<u64> {
+        require_keys_eq!(
+            accounts.random.key(),
+            slot_hashes::ID,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        Ok(Clock::get()?.slot)
+    }
+
+    fn reveal(random: &AccountInfo, force: &[u8; 32], commit_slot: u64) -> Result<Option<[u8; 64]>> {
+        require_keys_eq!(random.key(), slot_hashes::ID, NftAuctionError::InvalidRandomnessAccount);
+
+        let target_slot = commit_slot + SLOT_HASH_REVEAL_DELAY;
+        if Clock::get()?.slot <= target_slot {
+            return Ok(None);
+        }
+
+        // SlotHashes is a u64 length followed by (slot, hash) pairs, newest first
+        let data = random.try_borrow_data()?;
+        require!(data.len() >= 8, NftAuctionError::InvalidRandomnessAccount);
+        let len = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
+
+        // Oldest hash at or after the target slot, which may have been skipped
+        let mut target_hash: Option<&[u8]> = None;
+        for entry in data[8..].chunks_exact(40).take(len) {
+            let slot = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+            if slot < target_slot {
+                // Nothing at or after the target yet means the slots since were all skipped
+                return Ok(target_hash.map(|hash| expand_entropy(hash, force)));
+            }
+            target_hash = Some(&entry[8..40]);
+            if slot == target_slot {
+                return Ok(Some(expand_entropy(&entry[8..40], force)));
+            }
+        }
+
+        // The target slot has fallen out of the sysvar's window
+        Err(NftAuctionError::RandomnessExpired.into())
+    }
+}
+
+// Test-only provider: the request creates a `MockRandomness` PDA and `mock_fulfill` sets its value
+#[cfg(feature = "mock-vrf")]
+pub struct MockVrf;
+
+#[cfg(feature = "mock-vrf")]
+impl RandomnessSource for MockVrf {
+    fn request(accounts: &RandomnessRequest<'_, '_>, force: [u8; 32]) -> Result<u64> {
+        let (expected_random, bump) = Pubkey::find_program_address(
+            &[MOCK_RANDOMNESS_SEED, &force],
+            &crate::ID
+        );
+        require_keys_eq!(
+            accounts.random.key(),
+            expected_random,
+            NftAuctionError::InvalidRandomnessAccount
+        );
+
+        let space = 8 + MockRandomness::INIT_SPACE;
+        let signer_seeds: &[&[&[u8]]] = &[&[MOCK_RANDOMNESS_SEED, &force, &[bump]]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                accounts.system_program.clone(),
+                system_program::CreateAccount {
+                    from: accounts.payer.clone(),
+                    to: accounts.random.clone(),
+                },
+                signer_seeds
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID
+        )?;
+
+        let mock_randomness = MockRandomness {
+            force,
+            value: None,
+            bump,
+        };
+        let mut data = accounts.random.try_borrow_mut_data()?;
+        mock_randomness.try_serialize(&mut &mut data[..])?;
+
+        Ok(Clock::get()?.slot)
+    }
+
+    fn reveal(random: &AccountInfo, _force: &[u8; 32], _commit_slot: u64) -> Result<Option<[u8; 64]>> {
+        require_keys_eq!(*random.owner, crate::ID, NftAuctionError::InvalidRandomnessAccount);
+
+        let mock_randomness = MockRandomness::try_deserialize(&mut &random.try_borrow_data()?[..])?;
+        Ok(mock_randomness.value)
+    }
+}
@@ -1,20 +1,855 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::constants::{
+    MAX_CARDS_PER_PURCHASE,
+    MAX_NAME_LENGTH,
+    MAX_NFT_LIST_ENTRIES,
+    MAX_PAYMENT_MINTS,
+    MAX_POOL_ENTRIES,
+    MAX_REFERRAL_BALANCES,
+    MAX_ROLE_MEMBERS,
+    MAX_ROYALTY_CREATORS,
+    MAX_SET_TREES,
+    MAX_SYMBOL_LENGTH,
+    MAX_TREASURY_LIMITS,
+    MAX_URI_LENGTH,
+    MIN_WITHDRAWAL_TIMELOCK_SECS,
+    PURCHASE_DAY_SECS,
+    RARITY_TIERS,
+    WITHDRAWAL_WINDOW_SECS,
+};
 
+// Versioning: every account but the test-only `MockRandomness` carries a `version` set to its
+// type's `VERSION` at creation, so an upgrade can tell old layouts apart and migrate them in place
+// instead of orphaning them. It is the first field, where `migrate_account` reads it, except on
+// `Config`: that layout predates versioning, so its version is appended and `migrate_config`
+// tells legacy layouts apart by size.
 #[account]
+#[derive(InitSpace)]
 pub struct Config {
     pub admin: Pubkey,
     pub platform_vault: Pubkey,
     pub fee_percentage: u16,
+    // Stablecoin packs are priced in and instant sells pay out in; checked once at `initialize`
+    pub usdc_mint: Pubkey,
+    // Appended after the original layouts, see `migrate_config`
+    pub version: u8,
+    pub reserved: [u8; 64],
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+impl Config {
+    pub const VERSION: u8 = 1;
+    // Unversioned layouts: without and with `usdc_mint`
+    pub const LEGACY_SPACE: usize = 8 + 32 + 32 + 2;
+    pub const LEGACY_USDC_SPACE: usize = Config::LEGACY_SPACE + 32;
+
+    // What `instant_sell` pays for a pack before reserve scaling
+    pub fn buyback_price(&self, pack_price: u64) -> u64 {
+        (((pack_price as u128) * (10000u16.saturating_sub(self.fee_percentage) as u128)) /
+            10000) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct RoyaltyCreator {
+    pub address: Pubkey,
+    pub share: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionConfig {
+    pub version: u8,
+    pub collection_mint: Pubkey,
+    pub seller_fee_basis_points: u16,
+    #[max_len(MAX_ROYALTY_CREATORS)]
+    pub creators: Vec<RoyaltyCreator>,
+    pub bump: u8,
+}
+
+impl CollectionConfig {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CardSet {
+    pub version: u8,
+    pub collection_mint: Pubkey,
+    #[max_len(MAX_NAME_LENGTH)]
+    pub name: String,
+    #[max_len(MAX_SET_TREES)]
+    pub merkle_trees: Vec<Pubkey>,
+    pub cards_by_rarity: [u16; RARITY_TIERS],
+    pub pack_config_count: u8,
+    // USDC base units paid out per burned card of each tier
+    pub burn_rewards: [u64; RARITY_TIERS],
+    // Number of same-tier cards burned to craft one card of the next tier; 0 disables crafting
+    pub craft_input_count: u8,
+    pub bump: u8,
+}
+
+impl CardSet {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CardDefinition {
+    pub version: u8,
+    pub card_set: Pubkey,
+    pub rarity: u8,
+    pub index: u16,
+    // Pack the card is sold in; its price sets what `instant_sell` pays for the card
+    pub pack_id: u8,
+    #[max_len(MAX_NAME_LENGTH)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LENGTH)]
+    pub symbol: String,
+    #[max_len(MAX_URI_LENGTH)]
+    pub uri: String,
+    pub minted: u64,
+    pub burned: u64,
+    pub evolves_into: Option<Pubkey>,
+    pub evolution_cost: EvolutionCost,
+    pub bump: u8,
+}
+
+impl CardDefinition {
+    pub const VERSION: u8 = 1;
+}
+
+// Metadata one leaf was moved to by `update_card_metadata`; its siblings keep the definition's
+#[account]
+#[derive(InitSpace)]
+pub struct LeafMetadata {
+    pub version: u8,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub card_definition: Pubkey,
+    pub data_hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl LeafMetadata {
+    pub const VERSION: u8 = 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum EvolutionCost {
+    Free,
+    // USDC base units paid into the platform vault
+    Usdc {
+        amount: u64,
+    },
+    // A second leaf of the same card definition is burned alongside the source
+    Duplicate,
+    // Seconds the owner must wait after `start_evolution`
+    Staking {
+        duration: i64,
+    },
+}
+
+impl CardDefinition {
+    pub fn circulating_supply(&self) -> u64 {
+        self.minted.saturating_sub(self.burned)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PackConfig {
+    pub version: u8,
+    pub card_set: Pubkey,
+    pub pack_id: u8,
+    // USDC price, used when the payment mint has no entry in `payment_prices`.
+    // Also the USD price SOL is charged at through the oracle when SOL has no fixed price.
+    pub price: u64,
+    pub cards_per_pack: u8,
+    // Active drop table; purchases are refused until one is activated
+    pub drop_table: Pubkey,
+    pub drop_table_version: u32,
+    // Finite card pool drawn without replacement; default key when packs draw from the drop table
+    pub pack_pool: Pubkey,
+    pub randomness_provider: RandomnessProvider,
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub payment_prices: Vec<PaymentPrice>,
+    pub purchase_limits: PurchaseLimits,
+    pub bump: u8,
+}
+
+impl PackConfig {
+    pub const VERSION: u8 = 1;
+
+    // Price of one pack in base units of `mint`
+    pub fn price_for(&self, mint: Pubkey, usdc_mint: Pubkey) -> Result<u64> {
+        if let Some(payment_price) = self.payment_prices.iter().find(|p| p.mint == mint) {
+            return Ok(payment_price.price);
+        }
+        require_keys_eq!(mint, usdc_mint, crate::errors::NftAuctionError::PaymentMintNotAccepted);
+        Ok(self.price)
+    }
+}
+
+// Per-wallet limits against bots sweeping a drop; zero disables a limit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct PurchaseLimits {
+    pub max_packs_per_purchase: u8,
+    pub max_packs_per_wallet: u32,
+    // Per UTC day
+    pub max_packs_per_day: u32,
+    pub min_slot_gap: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub struct PaymentPrice {
+    // `SOL_PAYMENT_MINT` for lamports
+    pub mint: Pubkey,
+    pub price: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RandomnessProvider {
+    Orao,
+    Switchboard,
+    // Commit-reveal over the SlotHashes sysvar, for when no oracle is available
+    SlotHash,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub struct PoolEntry {
+    pub rarity: u8,
+    pub index: u16,
+    pub remaining: u32,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PackPool {
+    pub version: u8,
+    pub pack_config: Pubkey,
+    #[max_len(MAX_POOL_ENTRIES)]
+    pub entries: Vec<PoolEntry>,
+    pub total_remaining: u64,
+    // Cards owed to purchases that have not been revealed yet
+    pub reserved: u64,
+    pub bump: u8,
+}
+
+impl PackPool {
+    pub const VERSION: u8 = 1;
+
+    pub fn available(&self) -> u64 {
+        self.total_remaining.saturating_sub(self.reserved)
+    }
+}
+
+// Published odds for a pack config; frozen once activated
+#[account]
+#[derive(InitSpace)]
+pub struct DropTable {
+    // Account layout version; `version` below numbers the table itself
+    pub layout_version: u8,
+    pub pack_config: Pubkey,
+    pub version: u32,
+    pub rarity_weights: [u16; RARITY_TIERS],
+    // Cards per tier at activation; reveals index into this snapshot
+    pub tier_sizes: [u16; RARITY_TIERS],
+    // Packs opened without a card of `pity_rarity` or better before one is guaranteed; 0 disables
+    pub pity_threshold: u16,
+    pub pity_rarity: u8,
+    pub activated: bool,
+    pub activated_at: i64,
+    pub hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl DropTable {
+    pub const LAYOUT_VERSION: u8 = 1;
+
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let mut data = Vec::with_capacity(32 + 4 + RARITY_TIERS * 4 + 3);
+        data.extend_from_slice(self.pack_config.as_ref());
+        data.extend_from_slice(&self.version.to_le_bytes());
+        for weight in self.rarity_weights.iter() {
+            data.extend_from_slice(&weight.to_le_bytes());
+        }
+        for tier_size in self.tier_sizes.iter() {
+            data.extend_from_slice(&tier_size.to_le_bytes());
+        }
+        data.extend_from_slice(&self.pity_threshold.to_le_bytes());
+        data.push(self.pity_rarity);
+
+        hash(&data).to_bytes()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct DrawnCard {
+    pub rarity: u8,
+    pub index: u16,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PackPurchase {
+    pub version: u8,
+    pub user: Pubkey,
+    pub card_set: Pubkey,
+    pub pack_config: Pubkey,
+    pub force: [u8; 32],
+    pub pack_count: u8,
+    pub cards_per_pack: u8,
+    pub drop_table: Pubkey,
+    pub drop_table_version: u32,
+    pub pack_pool: Pubkey,
+    pub randomness_provider: RandomnessProvider,
+    // Account the randomness is read from at reveal time
+    pub randomness: Pubkey,
+    // Force of the current randomness request; differs from `force` after `rerequest_randomness`
+    pub request_seed: [u8; 32],
+    pub commit_slot: u64,
+    // Position among the buyer's purchases of this pack config; reveals must follow it
+    pub sequence: u64,
+    // Buyer's pity counter right before the reveal, recorded so the draw can be replayed
+    pub pity_before: u16,
+    pub revealed: bool,
+    #[max_len(MAX_CARDS_PER_PURCHASE)]
+    pub cards: Vec<DrawnCard>,
+    pub bump: u8,
+}
+
+impl PackPurchase {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CraftRequest {
+    pub version: u8,
+    pub user: Pubkey,
+    pub card_set: Pubkey,
+    pub target_rarity: u8,
+    pub force: [u8; 32],
+    pub randomness: Pubkey,
+    pub revealed: bool,
+    pub card: Option<DrawnCard>,
+    pub bump: u8,
+}
+
+impl CraftRequest {
+    pub const VERSION: u8 = 1;
+}
+
+// The staked leaf is delegated to this PDA, so transferring it away voids the stake
+#[account]
+#[derive(InitSpace)]
+pub struct EvolutionStake {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub card_definition: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub started_at: i64,
+    pub bump: u8,
+}
+
+impl EvolutionStake {
+    pub const VERSION: u8 = 1;
+}
+
+// Permanent provenance entry for one evolved leaf
+#[account]
+#[derive(InitSpace)]
+pub struct EvolutionRecord {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub source_definition: Pubkey,
+    pub target_definition: Pubkey,
+    pub cost: EvolutionCost,
+    pub evolved_at: i64,
+    // Set once `claim_evolved_card` has minted the target card
+    pub claimed: bool,
+    // Leaf the target card was minted as, for following provenance forward
+    pub evolved_tree: Pubkey,
+    pub evolved_nonce: u64,
+    pub bump: u8,
+}
+
+impl EvolutionRecord {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub version: u8,
+    pub user: Pubkey,
+    // Referrer credited on the first referred purchase; it can't change afterwards
+    pub referrer: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    pub const VERSION: u8 = 1;
+}
+
+// One per (player, pack config), so a wallet can open any number of pack types
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerPackState {
+    pub version: u8,
+    pub user: Pubkey,
+    pub pack_config: Pubkey,
+    pub packs_since_pity: u16,
+    pub total_packs: u32,
+    // Days since the Unix epoch that `packs_today` counts
+    pub day: i64,
+    pub packs_today: u32,
+    pub last_purchase_slot: u64,
+    // Purchases are revealed in the order they were made, so a player can't pick which one lands
+    // on the pity threshold
+    pub purchases: u64,
+    pub revealed_purchases: u64,
+    pub bump: u8,
+}
+
+impl PlayerPackState {
+    pub const VERSION: u8 = 1;
+
+    // Checks a purchase of `pack_count` packs against the pack config's limits and records it
+    pub fn record_purchase(
+        &mut self,
+        limits: &PurchaseLimits,
+        pack_count: u8,
+        now: i64,
+        slot: u64
+    ) -> Result<()> {
+        require!(
+            limits.max_packs_per_purchase == 0 || pack_count <= limits.max_packs_per_purchase,
+            crate::errors::NftAuctionError::PacksPerPurchaseExceeded
+        );
+
+        if self.total_packs > 0 {
+            require!(
+                slot >= self.last_purchase_slot.saturating_add(limits.min_slot_gap),
+                crate::errors::NftAuctionError::PurchaseCooldown
+            );
+        }
+
+        let day = now.div_euclid(PURCHASE_DAY_SECS);
+        if self.day != day {
+            self.day = day;
+            self.packs_today = 0;
+        }
+
+        let total_packs = self.total_packs.saturating_add(pack_count as u32);
+        let packs_today = self.packs_today.saturating_add(pack_count as u32);
+        require!(
+            limits.max_packs_per_wallet == 0 || total_packs <= limits.max_packs_per_wallet,
+            crate::errors::NftAuctionError::WalletPackLimitReached
+        );
+        require!(
+            limits.max_packs_per_day == 0 || packs_today <= limits.max_packs_per_day,
+            crate::errors::NftAuctionError::DailyPackLimitReached
+        );
+
+        self.total_packs = total_packs;
+        self.packs_today = packs_today;
+        self.last_purchase_slot = slot;
+
+        Ok(())
+    }
+}
+
+// Basis-point split of every payment into the program; the four shares sum to 10000
+#[account]
+#[derive(InitSpace)]
+pub struct FeeSchedule {
+    pub version: u8,
+    pub treasury: Pubkey,
+    pub creator: Pubkey,
+    pub partner: Pubkey,
+    pub treasury_bps: u16,
+    pub creator_bps: u16,
+    pub partner_bps: u16,
+    // Paid to the referral pool PDA
+    pub referral_bps: u16,
+    pub bump: u8,
+}
+
+#[derive(Clone)]
+pub struct FeeSplit {
+    pub treasury: u64,
+    pub creator: u64,
+    pub partner: u64,
+    pub referral: u64,
+}
+
+impl FeeSchedule {
+    pub const VERSION: u8 = 1;
+
+    // Rounding dust goes to the treasury so the shares always add up to `amount`
+    pub fn split(&self, amount: u64) -> FeeSplit {
+        let share = |bps: u16| (((amount as u128) * (bps as u128)) / 10000) as u64;
+        let creator = share(self.creator_bps);
+        let partner = share(self.partner_bps);
+        let referral = share(self.referral_bps);
+
+        FeeSplit {
+            treasury: amount - creator - partner - referral,
+            creator,
+            partner,
+            referral,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct ReferralBalance {
+    // `SOL_PAYMENT_MINT` for lamports
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub version: u8,
+    pub authority: Pubkey,
+    #[max_len(MAX_REFERRAL_BALANCES)]
+    pub balances: Vec<ReferralBalance>,
+    pub referred_purchases: u64,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const VERSION: u8 = 1;
+
+    pub fn credit(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        match self.balances.iter_mut().find(|b| b.mint == mint) {
+            Some(balance) => {
+                balance.amount = balance.amount
+                    .checked_add(amount)
+                    .ok_or(crate::errors::NftAuctionError::InvalidCount)?;
+            }
+            None => {
+                require!(
+                    self.balances.len() < MAX_REFERRAL_BALANCES,
+                    crate::errors::NftAuctionError::TooManyReferralBalances
+                );
+                self.balances.push(ReferralBalance { mint, amount });
+            }
+        }
+        Ok(())
+    }
+
+    // Zeroes the balance of `mint` and returns what was owed
+    pub fn take(&mut self, mint: Pubkey) -> Result<u64> {
+        let balance = self.balances
+            .iter_mut()
+            .find(|b| b.mint == mint && b.amount > 0)
+            .ok_or(crate::errors::NftAuctionError::NothingToClaim)?;
+        let amount = balance.amount;
+        balance.amount = 0;
+        Ok(amount)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct WithdrawalLimit {
+    // `SOL_PAYMENT_MINT` for lamports
+    pub mint: Pubkey,
+    pub daily_cap: u64,
+    // Amount counted against the cap as of `updated_at`; it drains linearly over 24h
+    pub used: u64,
+    pub updated_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    // Grants and revokes roles; passes every role check
+    SuperAdmin,
+    Minter,
+    // Card definitions, pack configs, drop tables, pools, card economy, payment prices and buyback
+    // reserve settings
+    PriceManager,
+    // Pausing buybacks, cancelling queued withdrawals and re-requesting stale randomness
+    Guardian,
+    // Treasury withdrawals and limits
+    Treasurer,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct RoleAssignment {
+    pub authority: Pubkey,
+    pub role: Role,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub version: u8,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub members: Vec<RoleAssignment>,
+    pub bump: u8,
+}
+
+impl Roles {
+    pub const VERSION: u8 = 1;
+
+    // Used in account constraints: `constraint = roles.has_role(signer.key, Role::X) @ MissingRole`
+    pub fn has_role(&self, authority: &Pubkey, role: Role) -> bool {
+        self.members
+            .iter()
+            .any(|m| m.authority == *authority && (m.role == role || m.role == Role::SuperAdmin))
+    }
+
+    pub fn grant(&mut self, authority: Pubkey, role: Role) -> Result<()> {
+        if self.members.iter().any(|m| m.authority == authority && m.role == role) {
+            return Ok(());
+        }
+        require!(
+            self.members.len() < MAX_ROLE_MEMBERS,
+            crate::errors::NftAuctionError::TooManyRoleMembers
+        );
+        self.members.push(RoleAssignment { authority, role });
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, authority: Pubkey, role: Role) -> Result<()> {
+        require!(
+            role != Role::SuperAdmin ||
+                self.members.iter().any(|m| m.role == Role::SuperAdmin && m.authority != authority),
+            crate::errors::NftAuctionError::LastSuperAdmin
+        );
+        self.members.retain(|m| !(m.authority == authority && m.role == role));
+        Ok(())
+    }
+}
+
+// Program-owned holder of platform revenue (set as the fee schedule's treasury wallet).
+// Holds SOL directly and is the authority of its token ATAs.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub version: u8,
+    // Seconds a queued withdrawal waits before it can execute
+    pub timelock: i64,
+    #[max_len(MAX_TREASURY_LIMITS)]
+    pub limits: Vec<WithdrawalLimit>,
+    pub next_request_id: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const VERSION: u8 = 1;
+
+    pub fn set_timelock(&mut self, timelock: i64) -> Result<()> {
+        require!(
+            timelock >= MIN_WITHDRAWAL_TIMELOCK_SECS,
+            crate::errors::NftAuctionError::TimelockTooShort
+        );
+        self.timelock = timelock;
+        Ok(())
+    }
+
+    pub fn set_limit(&mut self, mint: Pubkey, daily_cap: u64) -> Result<()> {
+        match self.limits.iter_mut().find(|l| l.mint == mint) {
+            Some(limit) => {
+                limit.daily_cap = daily_cap;
+            }
+            None => {
+                require!(
+                    self.limits.len() < MAX_TREASURY_LIMITS,
+                    crate::errors::NftAuctionError::TooManyTreasuryLimits
+                );
+                self.limits.push(WithdrawalLimit {
+                    mint,
+                    daily_cap,
+                    used: 0,
+                    updated_at: 0,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Rolling cap: past withdrawals stop counting proportionally as they age out of the window.
+    // Mints without a limit have a cap of zero, so all their withdrawals must be queued.
+    pub fn consume_limit(&mut self, mint: Pubkey, amount: u64, now: i64) -> Result<()> {
+        let limit = self.limits
+            .iter_mut()
+            .find(|l| l.mint == mint)
+            .ok_or(crate::errors::NftAuctionError::TreasuryCapExceeded)?;
+
+        let elapsed = now.saturating_sub(limit.updated_at).clamp(0, WITHDRAWAL_WINDOW_SECS);
+        let window = WITHDRAWAL_WINDOW_SECS as u128;
+        let drained = ((limit.daily_cap as u128) * (elapsed as u128)) / window;
+
+        let used = limit.used
+            .saturating_sub(drained as u64)
+            .checked_add(amount)
+            .filter(|used| *used <= limit.daily_cap)
+            .ok_or(crate::errors::NftAuctionError::TreasuryCapExceeded)?;
+        limit.used = used;
+        limit.updated_at = now;
+        Ok(())
+    }
+}
+
+// Withdrawal above the daily cap, executable once `executable_at` has passed
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalRequest {
+    pub version: u8,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const VERSION: u8 = 1;
+}
+
+// USDC set aside for `instant_sell`, separate from pack revenue. `reserve_ratio_bps` is kept up to
+// date for frontends; buybacks scale down below the target ratio and stop below the pause ratio.
+#[account]
+#[derive(InitSpace)]
+pub struct BuybackReserve {
+    pub version: u8,
+    // Share of each USDC pack payment moved into the reserve
+    pub top_up_bps: u16,
+    pub target_ratio_bps: u16,
+    pub pause_ratio_bps: u16,
+    // Manual pause, independent of the ratio
+    pub paused: bool,
+    pub reserve_balance: u64,
+    // Nominal buyback value of every pack sold and not yet bought back
+    pub outstanding_liabilities: u64,
+    // `reserve_balance / outstanding_liabilities`; `u64::MAX` with no liabilities
+    pub reserve_ratio_bps: u64,
+    pub bump: u8,
+}
+
+impl BuybackReserve {
+    pub const VERSION: u8 = 1;
+
+    pub fn top_up_amount(&self, payment: u64) -> u64 {
+        (((payment as u128) * (self.top_up_bps as u128)) / 10000) as u64
+    }
+
+    pub fn refresh(&mut self, reserve_balance: u64) {
+        self.reserve_balance = reserve_balance;
+        self.reserve_ratio_bps = if self.outstanding_liabilities == 0 {
+            u64::MAX
+        } else {
+            let ratio =
+                ((reserve_balance as u128) * 10000) / (self.outstanding_liabilities as u128);
+            u64::try_from(ratio).unwrap_or(u64::MAX)
+        };
+    }
+
+    pub fn add_liability(&mut self, amount: u64) {
+        self.outstanding_liabilities = self.outstanding_liabilities.saturating_add(amount);
+        self.refresh(self.reserve_balance);
+    }
+
+    pub fn settle_liability(&mut self, amount: u64) {
+        self.outstanding_liabilities = self.outstanding_liabilities.saturating_sub(amount);
+        self.refresh(self.reserve_balance);
+    }
+
+    pub fn scaled_buyback(&self, nominal: u64) -> Result<u64> {
+        require!(
+            !self.paused && self.reserve_ratio_bps >= (self.pause_ratio_bps as u64),
+            crate::errors::NftAuctionError::BuybacksPaused
+        );
+
+        let target = self.target_ratio_bps as u64;
+        if self.reserve_ratio_bps >= target {
+            return Ok(nominal);
+        }
+        Ok((((nominal as u128) * (self.reserve_ratio_bps as u128)) / (target as u128)) as u64)
+    }
+}
+
+// Ties a randomness account (and force, for SlotHashes) to the one request allowed to reveal it
+#[account]
+#[derive(InitSpace)]
+pub struct RandomnessBinding {
+    pub version: u8,
+    pub randomness: Pubkey,
+    pub request: Pubkey,
+    pub consumed: bool,
+    pub bump: u8,
+}
+
+impl RandomnessBinding {
+    pub const VERSION: u8 = 1;
+
+    pub fn bind(&mut self, randomness: Pubkey, request: Pubkey, bump: u8) -> Result<()> {
+        require!(
+            self.request == Pubkey::default(),
+            crate::errors::NftAuctionError::RandomnessAlreadyBound
+        );
+        self.randomness = randomness;
+        self.request = request;
+        self.consumed = false;
+        self.version = RandomnessBinding::VERSION;
+        self.bump = bump;
+        Ok(())
+    }
+
+    pub fn consume(&mut self, request: Pubkey) -> Result<()> {
+        require_keys_eq!(
+            self.request,
+            request,
+            crate::errors::NftAuctionError::RandomnessBindingMismatch
+        );
+        require!(!self.consumed, crate::errors::NftAuctionError::RandomnessAlreadyConsumed);
+        self.consumed = true;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct NftList {
+    pub version: u8,
+    pub card_set: Pubkey,
+    #[max_len(MAX_NFT_LIST_ENTRIES)]
+    pub entries: Vec<MetadataEntry>,
+    pub bump: u8,
+}
+
+impl NftList {
+    pub const VERSION: u8 = 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
 pub struct MetadataEntry {
     pub asset_id: Pubkey,
+    #[max_len(MAX_NAME_LENGTH)]
     pub name: String,
+    #[max_len(MAX_SYMBOL_LENGTH)]
     pub symbol: String,
+    #[max_len(MAX_URI_LENGTH)]
     pub uri: String,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LeafArgs {
+    pub root: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct TransferArgs {
     pub root: [u8; 32],
@@ -23,3 +858,347 @@ pub struct TransferArgs {
     pub nonce: u64,
     pub index: u32,
 }
+
+#[cfg(feature = "mock-vrf")]
+#[account]
+#[derive(InitSpace)]
+pub struct MockRandomness {
+    pub force: [u8; 32],
+    pub value: Option<[u8; 64]>,
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::NftAuctionError;
+
+    fn error_code<T>(result: Result<T>) -> u32 {
+        match result {
+            Err(Error::AnchorError(error)) => error.error_code_number,
+            _ => panic!("expected an anchor error"),
+        }
+    }
+
+    fn fee_schedule(shares_bps: [u16; 4]) -> FeeSchedule {
+        FeeSchedule {
+            version: FeeSchedule::VERSION,
+            treasury: Pubkey::default(),
+            creator: Pubkey::default(),
+            partner: Pubkey::default(),
+            treasury_bps: shares_bps[0],
+            creator_bps: shares_bps[1],
+            partner_bps: shares_bps[2],
+            referral_bps: shares_bps[3],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn fee_split_gives_rounding_dust_to_treasury() {
+        let split = fee_schedule([2500, 2500, 2500, 2500]).split(7);
+
+        // Each share of 7 * 25% rounds down to 1; the treasury keeps the remaining 4
+        assert_eq!(split.creator, 1);
+        assert_eq!(split.partner, 1);
+        assert_eq!(split.referral, 1);
+        assert_eq!(split.treasury, 4);
+    }
+
+    #[test]
+    fn fee_split_adds_up_to_amount() {
+        let schedule = fee_schedule([3333, 3333, 3333, 1]);
+
+        for amount in [0, 1, 9_999, 10_001, 123_456_789, u64::MAX] {
+            let split = schedule.split(amount);
+            let total = (split.treasury as u128) +
+                (split.creator as u128) +
+                (split.partner as u128) +
+                (split.referral as u128);
+            assert_eq!(total, amount as u128);
+            assert!(split.treasury >= split.creator);
+        }
+    }
+
+    #[test]
+    fn fee_split_exact_shares_leave_no_dust() {
+        let split = fee_schedule([4000, 3000, 2000, 1000]).split(10_000);
+
+        assert_eq!(split.treasury, 4000);
+        assert_eq!(split.creator, 3000);
+        assert_eq!(split.partner, 2000);
+        assert_eq!(split.referral, 1000);
+    }
+
+    fn buyback_reserve(target_ratio_bps: u16, pause_ratio_bps: u16) -> BuybackReserve {
+        BuybackReserve {
+            version: BuybackReserve::VERSION,
+            top_up_bps: 0,
+            target_ratio_bps,
+            pause_ratio_bps,
+            paused: false,
+            reserve_balance: 0,
+            outstanding_liabilities: 0,
+            reserve_ratio_bps: u64::MAX,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn buyback_pays_nominal_at_target_ratio() {
+        let mut reserve = buyback_reserve(5000, 2000);
+        reserve.add_liability(1_000);
+        reserve.refresh(500);
+
+        assert_eq!(reserve.reserve_ratio_bps, 5000);
+        assert_eq!(reserve.scaled_buyback(100).unwrap(), 100);
+    }
+
+    #[test]
+    fn buyback_scales_below_target_ratio() {
+        let mut reserve = buyback_reserve(5000, 2000);
+        reserve.add_liability(1_000);
+        reserve.refresh(250);
+
+        // Half the target ratio pays half the nominal
+        assert_eq!(reserve.reserve_ratio_bps, 2500);
+        assert_eq!(reserve.scaled_buyback(100).unwrap(), 50);
+    }
+
+    #[test]
+    fn buyback_still_pays_exactly_at_pause_ratio() {
+        let mut reserve = buyback_reserve(5000, 2000);
+        reserve.add_liability(1_000);
+        reserve.refresh(200);
+
+        assert_eq!(reserve.reserve_ratio_bps, 2000);
+        assert_eq!(reserve.scaled_buyback(100).unwrap(), 40);
+
+        reserve.refresh(199);
+        assert_eq!(
+            error_code(reserve.scaled_buyback(100)),
+            u32::from(NftAuctionError::BuybacksPaused)
+        );
+    }
+
+    #[test]
+    fn buyback_pays_nominal_without_liabilities() {
+        let mut reserve = buyback_reserve(5000, 2000);
+        reserve.refresh(0);
+
+        assert_eq!(reserve.reserve_ratio_bps, u64::MAX);
+        assert_eq!(reserve.scaled_buyback(100).unwrap(), 100);
+
+        // Settling more than is outstanding floors at zero liabilities
+        reserve.add_liability(100);
+        reserve.settle_liability(150);
+        assert_eq!(reserve.outstanding_liabilities, 0);
+        assert_eq!(reserve.reserve_ratio_bps, u64::MAX);
+    }
+
+    #[test]
+    fn buyback_manual_pause_overrides_ratio() {
+        let mut reserve = buyback_reserve(5000, 2000);
+        reserve.paused = true;
+
+        assert_eq!(
+            error_code(reserve.scaled_buyback(100)),
+            u32::from(NftAuctionError::BuybacksPaused)
+        );
+    }
+
+    fn roles(super_admin: Pubkey) -> Roles {
+        let mut roles = Roles {
+            version: Roles::VERSION,
+            members: Vec::new(),
+            bump: 0,
+        };
+        roles.grant(super_admin, Role::SuperAdmin).unwrap();
+        roles
+    }
+
+    #[test]
+    fn grant_is_idempotent_and_super_admin_passes_every_check() {
+        let admin = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let mut roles = roles(admin);
+
+        roles.grant(guardian, Role::Guardian).unwrap();
+        roles.grant(guardian, Role::Guardian).unwrap();
+        assert_eq!(roles.members.len(), 2);
+
+        assert!(roles.has_role(&guardian, Role::Guardian));
+        assert!(!roles.has_role(&guardian, Role::Treasurer));
+        assert!(roles.has_role(&admin, Role::Treasurer));
+    }
+
+    #[test]
+    fn grant_rejects_members_past_the_limit() {
+        let mut roles = roles(Pubkey::new_unique());
+        while roles.members.len() < MAX_ROLE_MEMBERS {
+            roles.grant(Pubkey::new_unique(), Role::Minter).unwrap();
+        }
+
+        assert_eq!(
+            error_code(roles.grant(Pubkey::new_unique(), Role::Minter)),
+            u32::from(NftAuctionError::TooManyRoleMembers)
+        );
+        // Re-granting an existing assignment still succeeds when full
+        let member = roles.members[1].authority;
+        roles.grant(member, Role::Minter).unwrap();
+    }
+
+    #[test]
+    fn revoke_removes_only_that_assignment() {
+        let admin = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let mut roles = roles(admin);
+        roles.grant(member, Role::Minter).unwrap();
+        roles.grant(member, Role::Guardian).unwrap();
+
+        roles.revoke(member, Role::Minter).unwrap();
+        assert!(!roles.has_role(&member, Role::Minter));
+        assert!(roles.has_role(&member, Role::Guardian));
+
+        // Revoking an assignment that doesn't exist is a no-op
+        roles.revoke(member, Role::Treasurer).unwrap();
+        assert_eq!(roles.members.len(), 2);
+    }
+
+    #[test]
+    fn revoke_rejects_removing_the_last_super_admin() {
+        let admin = Pubkey::new_unique();
+        let mut roles = roles(admin);
+
+        assert_eq!(
+            error_code(roles.revoke(admin, Role::SuperAdmin)),
+            u32::from(NftAuctionError::LastSuperAdmin)
+        );
+        assert!(roles.has_role(&admin, Role::SuperAdmin));
+
+        // With a second super admin either one can be removed, but not both
+        let other = Pubkey::new_unique();
+        roles.grant(other, Role::SuperAdmin).unwrap();
+        roles.revoke(admin, Role::SuperAdmin).unwrap();
+        assert!(!roles.has_role(&admin, Role::SuperAdmin));
+        assert_eq!(
+            error_code(roles.revoke(other, Role::SuperAdmin)),
+            u32::from(NftAuctionError::LastSuperAdmin)
+        );
+    }
+
+    const MINT: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const CAP: u64 = 1_000;
+
+    fn treasury_with_cap(daily_cap: u64) -> Treasury {
+        let mut treasury = Treasury {
+            version: Treasury::VERSION,
+            timelock: MIN_WITHDRAWAL_TIMELOCK_SECS,
+            limits: Vec::new(),
+            next_request_id: 0,
+            bump: 0,
+        };
+        treasury.set_limit(MINT, daily_cap).unwrap();
+        treasury
+    }
+
+    #[test]
+    fn consume_limit_allows_exactly_the_cap() {
+        let mut treasury = treasury_with_cap(CAP);
+
+        treasury.consume_limit(MINT, CAP, 0).unwrap();
+        assert_eq!(treasury.limits[0].used, CAP);
+    }
+
+    #[test]
+    fn consume_limit_rejects_one_over_the_cap() {
+        let mut treasury = treasury_with_cap(CAP);
+
+        assert_eq!(
+            error_code(treasury.consume_limit(MINT, CAP + 1, 0)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+
+        treasury.consume_limit(MINT, CAP - 1, 0).unwrap();
+        assert_eq!(
+            error_code(treasury.consume_limit(MINT, 2, 0)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+        // A failed withdrawal leaves the usage untouched
+        assert_eq!(treasury.limits[0].used, CAP - 1);
+    }
+
+    #[test]
+    fn consume_limit_decays_proportionally_over_the_window() {
+        let mut treasury = treasury_with_cap(CAP);
+        treasury.consume_limit(MINT, CAP, 0).unwrap();
+
+        // A quarter of the window frees a quarter of the cap
+        let quarter = WITHDRAWAL_WINDOW_SECS / 4;
+        assert_eq!(
+            error_code(treasury.consume_limit(MINT, CAP / 4 + 1, quarter)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+        treasury.consume_limit(MINT, CAP / 4, quarter).unwrap();
+        assert_eq!(treasury.limits[0].used, CAP);
+        assert_eq!(treasury.limits[0].updated_at, quarter);
+    }
+
+    #[test]
+    fn consume_limit_resets_after_a_full_window() {
+        let mut treasury = treasury_with_cap(CAP);
+        treasury.consume_limit(MINT, CAP, 0).unwrap();
+
+        // Elapsed time is clamped to the window, so waiting longer never frees more than the cap
+        treasury.consume_limit(MINT, CAP, 10 * WITHDRAWAL_WINDOW_SECS).unwrap();
+        assert_eq!(treasury.limits[0].used, CAP);
+    }
+
+    #[test]
+    fn consume_limit_ignores_clock_going_backwards() {
+        let mut treasury = treasury_with_cap(CAP);
+        treasury.consume_limit(MINT, CAP, 100).unwrap();
+
+        assert_eq!(
+            error_code(treasury.consume_limit(MINT, 1, 50)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+    }
+
+    #[test]
+    fn consume_limit_rejects_mints_without_a_limit() {
+        let mut treasury = treasury_with_cap(CAP);
+
+        assert_eq!(
+            error_code(treasury.consume_limit(Pubkey::new_unique(), 1, 0)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+    }
+
+    #[test]
+    fn consume_limit_zero_cap_blocks_every_withdrawal() {
+        let mut treasury = treasury_with_cap(0);
+
+        treasury.consume_limit(MINT, 0, 0).unwrap();
+        assert_eq!(
+            error_code(treasury.consume_limit(MINT, 1, WITHDRAWAL_WINDOW_SECS)),
+            u32::from(NftAuctionError::TreasuryCapExceeded)
+        );
+    }
+
+    #[test]
+    fn set_timelock_enforces_the_minimum() {
+        let mut treasury = treasury_with_cap(CAP);
+
+        assert_eq!(
+            error_code(treasury.set_timelock(MIN_WITHDRAWAL_TIMELOCK_SECS - 1)),
+            u32::from(NftAuctionError::TimelockTooShort)
+        );
+        assert_eq!(
+            error_code(treasury.set_timelock(0)),
+            u32::from(NftAuctionError::TimelockTooShort)
+        );
+        treasury.set_timelock(2 * MIN_WITHDRAWAL_TIMELOCK_SECS).unwrap();
+        assert_eq!(treasury.timelock, 2 * MIN_WITHDRAWAL_TIMELOCK_SECS);
+    }
+}
@@ -1,6 +1,472 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
-use crate::state::MetadataEntry;
+use anchor_lang::solana_program::{
+    instruction::{ AccountMeta, Instruction },
+    keccak,
+    program::{ invoke, invoke_signed },
+};
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID,
+    LEAF_METADATA_SEED,
+    MAX_NAME_LENGTH,
+    MAX_ROYALTY_CREATORS,
+    MAX_SELLER_FEE_BASIS_POINTS,
+    MAX_SYMBOL_LENGTH,
+    MAX_URI_LENGTH,
+};
+use crate::errors::NftAuctionError;
+use crate::instructions::mint_nft::{
+    Collection,
+    Creator,
+    MetadataArgs,
+    MintToCollectionV1Args,
+    TokenProgramVersion,
+    TokenStandard,
+};
+use crate::state::{
+    CardDefinition,
+    CollectionConfig,
+    LeafArgs,
+    LeafMetadata,
+    MetadataEntry,
+    RoyaltyCreator,
+    TransferArgs,
+};
+
+// Metadata exactly as `mint_nft` writes it for a card definition, so leaf hashes can be recomputed
+pub fn card_metadata_args(
+    card_definition: &CardDefinition,
+    collection_config: &CollectionConfig,
+    collection_authority: Pubkey
+) -> MetadataArgs {
+    let mut creators = vec![Creator {
+        address: collection_authority,
+        verified: true,
+        share: 0,
+    }];
+    creators.extend(
+        collection_config.creators.iter().map(|c| Creator {
+            address: c.address,
+            verified: false,
+            share: c.share,
+        })
+    );
+
+    MetadataArgs {
+        name: card_definition.name.clone(),
+        symbol: card_definition.symbol.clone(),
+        uri: card_definition.uri.clone(),
+        seller_fee_basis_points: collection_config.seller_fee_basis_points,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: true,
+            key: collection_config.collection_mint,
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators,
+    }
+}
+
+// Mirrors Bubblegum's leaf data hash: keccak(keccak(metadata) || seller_fee_basis_points)
+pub fn hash_metadata(metadata: &MetadataArgs) -> Result<[u8; 32]> {
+    let serialized = metadata.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?;
+    let metadata_hash = keccak::hashv(&[serialized.as_slice()]);
+
+    Ok(
+        keccak::hashv(
+            &[metadata_hash.as_ref(), &metadata.seller_fee_basis_points.to_le_bytes()]
+        ).to_bytes()
+    )
+}
+
+pub fn hash_creators(creators: &[Creator]) -> [u8; 32] {
+    let creator_data: Vec<Vec<u8>> = creators
+        .iter()
+        .map(|c| [c.address.as_ref(), &[c.verified as u8], &[c.share]].concat())
+        .collect();
+    let slices: Vec<&[u8]> = creator_data
+        .iter()
+        .map(|c| c.as_slice())
+        .collect();
+
+    keccak::hashv(&slices).to_bytes()
+}
+
+pub struct BurnLeafAccounts<'a, 'info> {
+    pub tree_authority: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub leaf_delegate: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+// Data and creator hashes of a card's leaf; a leaf updated on its own carries its record's hash
+pub fn card_leaf_hashes(
+    card_definition: &Account<CardDefinition>,
+    collection_config: &CollectionConfig,
+    collection_authority: Pubkey,
+    leaf: &LeafArgs,
+    leaf_metadata: Option<&LeafMetadata>
+) -> Result<([u8; 32], [u8; 32])> {
+    let metadata = card_metadata_args(card_definition, collection_config, collection_authority);
+    let data_hash = match leaf_metadata {
+        Some(record) => {
+            require!(
+                record.card_definition == card_definition.key() && record.nonce == leaf.nonce,
+                NftAuctionError::InvalidLeafMetadata
+            );
+            record.data_hash
+        }
+        None => hash_metadata(&metadata)?,
+    };
+    Ok((data_hash, hash_creators(&metadata.creators)))
+}
+
+// Leaf metadata record passed as a remaining account, checked against its PDA
+pub fn load_leaf_metadata(
+    info: &AccountInfo,
+    merkle_tree: &Pubkey,
+    nonce: u64
+) -> Result<LeafMetadata> {
+    require_keys_eq!(*info.owner, crate::ID, NftAuctionError::InvalidLeafMetadata);
+    let record = LeafMetadata::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let expected = Pubkey::create_program_address(
+        &[LEAF_METADATA_SEED, merkle_tree.as_ref(), &nonce.to_le_bytes(), &[record.bump]],
+        &crate::ID
+    ).map_err(|_| error!(NftAuctionError::InvalidLeafMetadata))?;
+    require_keys_eq!(info.key(), expected, NftAuctionError::InvalidLeafMetadata);
+    Ok(record)
+}
+
+// Bubblegum `burn`; the leaf owner must have signed the outer transaction
+pub fn burn_leaf<'info>(
+    accounts: BurnLeafAccounts<'_, 'info>,
+    leaf: &LeafArgs,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    proof: &[AccountInfo<'info>]
+) -> Result<()> {
+    let discriminator = hash(b"global:burn").to_bytes();
+    let mut data = discriminator[..8].to_vec();
+
+    #[derive(AnchorSerialize)]
+    struct BubblegumBurnArgs {
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+    }
+
+    let burn_args = BubblegumBurnArgs {
+        root: leaf.root,
+        data_hash,
+        creator_hash,
+        nonce: leaf.nonce,
+        index: leaf.index,
+    };
+    data.extend(burn_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), true),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false)
+    ];
+
+    let mut account_infos = vec![
+        accounts.tree_authority.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone()
+    ];
+
+    for proof_node in proof.iter() {
+        metas.push(AccountMeta::new_readonly(proof_node.key(), false));
+        account_infos.push(proof_node.clone());
+    }
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+
+    invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
+
+// Bubblegum `delegate`; proves the signer owns the leaf and hands its delegate to `new_delegate`
+pub fn delegate_leaf<'info>(
+    accounts: BurnLeafAccounts<'_, 'info>,
+    new_delegate: &AccountInfo<'info>,
+    leaf: &LeafArgs,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    proof: &[AccountInfo<'info>]
+) -> Result<()> {
+    let discriminator = hash(b"global:delegate").to_bytes();
+    let mut data = discriminator[..8].to_vec();
+
+    #[derive(AnchorSerialize)]
+    struct BubblegumDelegateArgs {
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+    }
+
+    let delegate_args = BubblegumDelegateArgs {
+        root: leaf.root,
+        data_hash,
+        creator_hash,
+        nonce: leaf.nonce,
+        index: leaf.index,
+    };
+    data.extend(delegate_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), true),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), false),
+        AccountMeta::new_readonly(new_delegate.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false)
+    ];
+
+    let mut account_infos = vec![
+        accounts.tree_authority.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        new_delegate.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone()
+    ];
+
+    for proof_node in proof.iter() {
+        metas.push(AccountMeta::new_readonly(proof_node.key(), false));
+        account_infos.push(proof_node.clone());
+    }
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+
+    invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
+
+// Bubblegum `transfer`; the leaf owner must have signed the outer transaction
+pub fn transfer_leaf<'info>(
+    accounts: BurnLeafAccounts<'_, 'info>,
+    new_owner: &AccountInfo<'info>,
+    transfer_args: &TransferArgs,
+    proof: &[AccountInfo<'info>]
+) -> Result<()> {
+    let discriminator = hash(b"global:transfer").to_bytes();
+    let mut data = discriminator[..8].to_vec();
+    data.extend(transfer_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), true),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), false),
+        AccountMeta::new_readonly(new_owner.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false)
+    ];
+
+    let mut account_infos = vec![
+        accounts.tree_authority.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        new_owner.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone()
+    ];
+
+    for proof_node in proof.iter() {
+        metas.push(AccountMeta::new_readonly(proof_node.key(), false));
+        account_infos.push(proof_node.clone());
+    }
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+
+    invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
+
+pub struct MintLeafAccounts<'a, 'info> {
+    pub tree_authority: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub leaf_delegate: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub tree_delegate: &'a AccountInfo<'info>,
+    pub collection_authority: &'a AccountInfo<'info>,
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub collection_mint: &'a AccountInfo<'info>,
+    pub collection_metadata: &'a AccountInfo<'info>,
+    pub collection_master_edition: &'a AccountInfo<'info>,
+    pub bubblegum_signer: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+// Nonce the next leaf minted into a tree gets: `num_minted` of its Bubblegum `TreeConfig`,
+// after the discriminator, creator, delegate and capacity
+pub fn next_leaf_nonce(tree_authority: &AccountInfo) -> Result<u64> {
+    const NUM_MINTED_OFFSET: usize = 8 + 32 + 32 + 8;
+
+    let data = tree_authority.try_borrow_data()?;
+    let bytes = data
+        .get(NUM_MINTED_OFFSET..NUM_MINTED_OFFSET + 8)
+        .ok_or(NftAuctionError::SerializeError)?;
+    Ok(u64::from_le_bytes(bytes.try_into().map_err(|_| error!(NftAuctionError::SerializeError))?))
+}
+
+// Bubblegum `mint_to_collection_v1` signed by the collection authority PDA
+pub fn mint_card_leaf<'info>(
+    accounts: MintLeafAccounts<'_, 'info>,
+    metadata: &MetadataArgs,
+    signer_seeds: &[&[&[u8]]]
+) -> Result<()> {
+    let discriminator = hash(b"global:mint_to_collection_v1").to_bytes();
+    let mut data = discriminator[..8].to_vec();
+
+    let mint_args = MintToCollectionV1Args {
+        metadata_args: metadata.clone(),
+    };
+    data.extend(mint_args.try_to_vec().map_err(|_| error!(NftAuctionError::SerializeError))?);
+
+    let metas = vec![
+        AccountMeta::new(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new(accounts.payer.key(), true),
+        AccountMeta::new_readonly(accounts.tree_delegate.key(), true),
+        AccountMeta::new_readonly(accounts.collection_authority.key(), true),
+        AccountMeta::new_readonly(accounts.bubblegum_program.key(), false),
+        AccountMeta::new_readonly(accounts.collection_mint.key(), false),
+        AccountMeta::new(accounts.collection_metadata.key(), false),
+        AccountMeta::new_readonly(accounts.collection_master_edition.key(), false),
+        AccountMeta::new_readonly(accounts.bubblegum_signer.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.token_metadata_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+        // Passed again as a trailing signer so Bubblegum accepts the PDA as a verified creator
+        AccountMeta::new_readonly(accounts.collection_authority.key(), true)
+    ];
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            accounts.tree_authority.clone(),
+            accounts.leaf_owner.clone(),
+            accounts.leaf_delegate.clone(),
+            accounts.merkle_tree.clone(),
+            accounts.payer.clone(),
+            accounts.tree_delegate.clone(),
+            accounts.collection_authority.clone(),
+            accounts.bubblegum_program.clone(),
+            accounts.collection_mint.clone(),
+            accounts.collection_metadata.clone(),
+            accounts.collection_master_edition.clone(),
+            accounts.bubblegum_signer.clone(),
+            accounts.log_wrapper.clone(),
+            accounts.compression_program.clone(),
+            accounts.token_metadata_program.clone(),
+            accounts.system_program.clone(),
+        ],
+        signer_seeds
+    )?;
+
+    Ok(())
+}
+
+pub fn validate_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(
+        name.len() <= MAX_NAME_LENGTH &&
+            symbol.len() <= MAX_SYMBOL_LENGTH &&
+            uri.len() <= MAX_URI_LENGTH,
+        NftAuctionError::MetadataTooLong
+    );
+    Ok(())
+}
+
+// Rejects what Token Metadata would refuse at mint time: duplicate creators, or a creator
+// colliding with the collection authority PDA that `card_metadata_args` always lists first
+pub fn validate_royalties(
+    seller_fee_basis_points: u16,
+    creators: &[RoyaltyCreator],
+    collection_authority: Pubkey
+) -> Result<()> {
+    require!(
+        seller_fee_basis_points <= MAX_SELLER_FEE_BASIS_POINTS,
+        NftAuctionError::InvalidRoyaltyBasisPoints
+    );
+    require!(creators.len() <= MAX_ROYALTY_CREATORS, NftAuctionError::TooManyCreators);
+
+    for (i, creator) in creators.iter().enumerate() {
+        require_keys_neq!(
+            creator.address,
+            collection_authority,
+            NftAuctionError::ReservedCreatorAddress
+        );
+        require!(
+            creators[..i].iter().all(|c| c.address != creator.address),
+            NftAuctionError::DuplicateCreator
+        );
+    }
+
+    let total_share: u16 = creators
+        .iter()
+        .map(|c| c.share as u16)
+        .sum();
+    require!(total_share == 100, NftAuctionError::InvalidCreatorShares);
+
+    Ok(())
+}
 
 pub fn extract_asset_data_from_mint(
     merkle_tree: &Pubkey,
@@ -0,0 +1,226 @@
+// End-to-end pack reveal against a local validator with the mock randomness provider:
+//   anchor test --provider.cluster localnet -- --no-default-features --features localnet,mock-vrf
+// Token Metadata is cloned from devnet (see `[test.validator]` in Anchor.toml) for the collection.
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import { createMint } from "@solana/spl-token";
+import { expect } from "chai";
+import { NftAuctionHouseSmartContract } from "../target/types/nft_auction_house_smart_contract";
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+const WITHDRAWAL_WINDOW_SECS = 24 * 60 * 60;
+const PACK_ID = 1;
+const CARDS_PER_PACK = 5;
+
+describe("mock-vrf pack reveal", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .NftAuctionHouseSmartContract as Program<NftAuctionHouseSmartContract>;
+  const wallet = provider.wallet as anchor.Wallet;
+  const admin = wallet.publicKey;
+
+  const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const collectionMint = Keypair.generate();
+  const cardSet = pda(Buffer.from("card_set"), collectionMint.publicKey.toBuffer());
+  const packConfig = pda(Buffer.from("pack_config"), cardSet.toBuffer(), Buffer.from([PACK_ID]));
+  const treasury = pda(Buffer.from("treasury"));
+
+  it("initializes the program, a card set and a pack config", async () => {
+    const usdcMint = await createMint(provider.connection, wallet.payer, admin, null, 6);
+
+    await program.methods
+      .initialize(admin, 500)
+      .accountsPartial({ admin, usdcMint })
+      .rpc();
+    await program.methods.initRoles().accountsPartial({ admin }).rpc();
+    await program.methods
+      .initTreasury(new BN(WITHDRAWAL_WINDOW_SECS))
+      .accountsPartial({ admin })
+      .rpc();
+    await program.methods
+      .setFeeSchedule(admin, admin, [10000, 0, 0, 0])
+      .accountsPartial({ admin })
+      .rpc();
+    await program.methods
+      .configureBuybackReserve(0, 10000, 5000, false)
+      .accountsPartial({ admin })
+      .rpc();
+
+    const [collectionMetadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        collectionMint.publicKey.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const [collectionMasterEdition] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        collectionMint.publicKey.toBuffer(),
+        Buffer.from("edition"),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    await program.methods
+      .createCollection("Test Set", "TST", "https://example.com/set.json", 500, [
+        { address: admin, share: 100 },
+      ])
+      .accountsPartial({
+        payer: admin,
+        minter: admin,
+        collectionMint: collectionMint.publicKey,
+        collectionMetadata,
+        collectionMasterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([collectionMint])
+      .rpc();
+
+    await program.methods
+      .addCardDefinition(0, 0, PACK_ID, "Common Card", "TST", "https://example.com/0.json")
+      .accountsPartial({ admin, cardSet })
+      .rpc();
+    await program.methods
+      .setPackConfig(PACK_ID, new BN(1_000_000), CARDS_PER_PACK)
+      .accountsPartial({ admin, cardSet })
+      .rpc();
+    // A fixed SOL price keeps the Pyth oracle out of the test
+    await program.methods
+      .setPaymentPrice(PublicKey.default, new BN(10_000_000))
+      .accountsPartial({ admin, cardSet, packConfig })
+      .rpc();
+
+    const dropTable = pda(
+      Buffer.from("drop_table"),
+      packConfig.toBuffer(),
+      new BN(1).toArrayLike(Buffer, "le", 4)
+    );
+    await program.methods
+      .createDropTable(1, [10000, 0, 0, 0, 0], 0, 0)
+      .accountsPartial({ admin, cardSet, packConfig, dropTable })
+      .rpc();
+    await program.methods
+      .activateDropTable()
+      .accountsPartial({ admin, cardSet, packConfig, dropTable })
+      .rpc();
+
+    const config = await program.account.packConfig.fetch(packConfig);
+    expect(config.dropTable.equals(dropTable)).to.be.true;
+  });
+
+  it("buys a pack, fulfills the mock randomness and reveals the cards", async () => {
+    const force = Array.from(Keypair.generate().publicKey.toBytes());
+    const random = pda(Buffer.from("mock_randomness"), Buffer.from(force));
+    const randomnessBinding = pda(Buffer.from("pack_randomness_binding"), random.toBuffer());
+    const packPurchase = pda(Buffer.from("pack_purchase"), Buffer.from(force));
+    const playerPackState = pda(
+      Buffer.from("player_pack_state"),
+      admin.toBuffer(),
+      packConfig.toBuffer()
+    );
+
+    const buyPack = await program.methods
+      .buyPack(force, PACK_ID, 1, null)
+      .accountsPartial({
+        user: admin,
+        cardSet,
+        packConfig,
+        packPurchase,
+        packPool: null,
+        playerPackState,
+        paymentMint: null,
+        referrer: null,
+        platformVault: admin,
+        reserveTokenAccount: null,
+        priceUpdate: null,
+        treasuryWallet: treasury,
+        creatorWallet: admin,
+        partnerWallet: admin,
+        treasuryPaymentAccount: null,
+        creatorPaymentAccount: null,
+        partnerPaymentAccount: null,
+        referralPaymentAccount: null,
+        userPaymentAccount: null,
+        treasury: null,
+        random,
+        randomnessBinding,
+        config: null,
+        vrf: null,
+      })
+      .instruction();
+    // The randomness account is read-only in the IDL since it may be the SlotHashes sysvar;
+    // the mock provider creates it during the purchase, so it has to be writable here
+    buyPack.keys.find((key) => key.pubkey.equals(random))!.isWritable = true;
+    await provider.sendAndConfirm(new Transaction().add(buyPack));
+
+    let purchase = await program.account.packPurchase.fetch(packPurchase);
+    expect(purchase.revealed).to.be.false;
+    expect(purchase.randomness.equals(random)).to.be.true;
+
+    // Not revealable until the mock is fulfilled
+    let stillProcessing = false;
+    try {
+      await program.methods
+        .fulfillRandom(force)
+        .accountsPartial({
+          platformVault: admin,
+          user: admin,
+          cardSet,
+          packConfig,
+          packPurchase,
+          dropTable: purchase.dropTable,
+          packPool: null,
+          playerPackState,
+          random,
+          randomnessBinding,
+        })
+        .rpc();
+    } catch (error) {
+      stillProcessing = `${error}`.includes("StillProcessing");
+    }
+    expect(stillProcessing).to.be.true;
+
+    await program.methods
+      .mockFulfill(force, Array.from({ length: 64 }, (_, i) => i))
+      .accountsPartial({ admin, mockRandomness: random })
+      .rpc();
+
+    await program.methods
+      .fulfillRandom(force)
+      .accountsPartial({
+        platformVault: admin,
+        user: admin,
+        cardSet,
+        packConfig,
+        packPurchase,
+        dropTable: purchase.dropTable,
+        packPool: null,
+        playerPackState,
+        random,
+        randomnessBinding,
+      })
+      .rpc();
+
+    purchase = await program.account.packPurchase.fetch(packPurchase);
+    expect(purchase.revealed).to.be.true;
+    expect(purchase.cards).to.have.length(CARDS_PER_PACK);
+    for (const card of purchase.cards) {
+      expect(card.rarity).to.equal(0);
+      expect(card.index).to.equal(0);
+    }
+
+    const binding = await program.account.randomnessBinding.fetch(randomnessBinding);
+    expect(binding.consumed).to.be.true;
+
+    const state = await program.account.playerPackState.fetch(playerPackState);
+    expect(state.revealedPurchases.toNumber()).to.equal(1);
+  });
+});